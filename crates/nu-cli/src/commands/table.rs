@@ -72,9 +72,7 @@ fn table(args: CommandArgs, registry: &CommandRegistry) -> Result<OutputStream,
                         Some(a) => {
                             if !new_input.is_empty() {
                                 if let Some(descs) = new_input.get(0) {
-                                    let descs = descs.data_descriptors();
-                                    let compare = a.data_descriptors();
-                                    if descs != compare {
+                                    if !descs.descriptors().eq(a.descriptors()) {
                                         delay_slot = Some(a);
                                         break;
                                     } else {
@@ -154,10 +154,10 @@ fn merge_descriptors(values: &[Value]) -> Vec<Spanned<String>> {
     let mut ret: Vec<Spanned<String>> = vec![];
     let mut seen: IndexSet<String> = indexset! {};
     for value in values {
-        for desc in value.data_descriptors() {
-            if !seen.contains(&desc[..]) {
-                seen.insert(desc.clone());
-                ret.push(desc.spanned(value.tag.span));
+        for desc in value.descriptors() {
+            if !seen.contains(desc) {
+                seen.insert(desc.to_string());
+                ret.push(desc.to_string().spanned(value.tag.span));
             }
         }
     }
@@ -206,7 +206,7 @@ pub mod value {
 
     pub fn column_path(paths: &[Value]) -> Result<Value, ShellError> {
         Ok(UntaggedValue::Primitive(Primitive::ColumnPath(
-            table(&paths.to_vec()).as_column_path()?.item,
+            ValueExt::as_column_path(&table(&paths.to_vec()))?.item,
         ))
         .into_untagged_value())
     }
@@ -10,6 +10,9 @@ mod type_name;
 mod type_shape;
 mod value;
 
+#[cfg(test)]
+mod test_support;
+
 pub use crate::call_info::{CallInfo, EvaluatedArgs};
 pub use crate::maybe_owned::MaybeOwned;
 pub use crate::return_value::{CommandAction, ReturnSuccess, ReturnValue};
@@ -18,9 +21,16 @@ pub use crate::syntax_shape::SyntaxShape;
 pub use crate::type_name::{PrettyType, ShellTypeName, SpannedTypeName};
 pub use crate::type_shape::{Row as RowType, Type};
 pub use crate::value::column_path::{did_you_mean, ColumnPath, PathMember, UnspannedPathMember};
+pub use crate::value::convert::ini::{from_ini, to_ini};
+pub use crate::value::convert::json::NdjsonWriter;
+pub use crate::value::convert::markdown::table_to_markdown;
+pub use crate::value::convert::xml::{from_xml, to_xml};
 pub use crate::value::dict::{Dictionary, TaggedDictBuilder};
 pub use crate::value::evaluate::{Evaluate, EvaluateTrait, Scope};
-pub use crate::value::primitive::Primitive;
-pub use crate::value::primitive::{format_date, format_duration, format_primitive};
+pub use crate::value::primitive::{
+    format_date, format_duration, format_precise_duration, format_primitive,
+};
+pub use crate::value::primitive::{FilesizeUnit, Primitive};
 pub use crate::value::range::{Range, RangeInclusion};
-pub use crate::value::{merge_descriptors, UntaggedValue, Value};
+pub use crate::value::string::{PadSide, TrimSide};
+pub use crate::value::{default_renderer, merge_descriptors, UntaggedValue, Value};
@@ -0,0 +1,48 @@
+//! Small constructors for the untagged Values used throughout the unit tests
+
+use crate::value::{UntaggedValue, Value};
+use bigdecimal::BigDecimal;
+use indexmap::IndexMap;
+use std::str::FromStr;
+
+pub fn string(input: impl Into<String>) -> Value {
+    UntaggedValue::string(input.into()).into_untagged_value()
+}
+
+pub fn line(input: impl Into<String>) -> Value {
+    UntaggedValue::line(input.into()).into_untagged_value()
+}
+
+pub fn int(input: i64) -> Value {
+    UntaggedValue::int(input).into_untagged_value()
+}
+
+pub fn decimal(input: &str) -> Value {
+    UntaggedValue::decimal(BigDecimal::from_str(input).unwrap()).into_untagged_value()
+}
+
+pub fn boolean(input: bool) -> Value {
+    UntaggedValue::boolean(input).into_untagged_value()
+}
+
+pub fn binary(input: &[u8]) -> Value {
+    UntaggedValue::binary(input.to_vec()).into_untagged_value()
+}
+
+pub fn nothing() -> Value {
+    UntaggedValue::nothing().into_untagged_value()
+}
+
+pub fn table(list: &[Value]) -> Value {
+    UntaggedValue::table(list).into_untagged_value()
+}
+
+pub fn row(entries: Vec<(&str, Value)>) -> Value {
+    let mut map = IndexMap::new();
+
+    for (key, value) in entries {
+        map.insert(key.to_string(), value);
+    }
+
+    UntaggedValue::row(map).into_untagged_value()
+}
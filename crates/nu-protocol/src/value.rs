@@ -1,22 +1,34 @@
+mod binary;
+mod bits;
+mod coerce;
 pub mod column_path;
-mod convert;
+pub mod convert;
+mod date;
 mod debug;
 pub mod dict;
 pub mod evaluate;
+mod hash;
+mod math;
 pub mod primitive;
 pub mod range;
+mod row;
 mod serde_bigdecimal;
 mod serde_bigint;
+mod size;
+pub mod string;
+mod table;
+mod url;
 
 use crate::type_name::{ShellTypeName, SpannedTypeName};
+use crate::value::coerce::primitive_to_string;
 use crate::value::dict::Dictionary;
 use crate::value::evaluate::Evaluate;
-use crate::value::primitive::Primitive;
+use crate::value::primitive::{format_primitive, Primitive};
 use crate::value::range::{Range, RangeInclusion};
 use crate::{ColumnPath, PathMember};
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use nu_errors::ShellError;
 use nu_source::{AnchorLocation, HasSpan, Span, Spanned, Tag};
 use num_bigint::BigInt;
@@ -61,6 +73,17 @@ impl UntaggedValue {
         }
     }
 
+    /// Iterate the descriptors (column names) of this value without copying them, for use in loops over many
+    /// values where `data_descriptors` would allocate for every value
+    pub fn descriptors(&self) -> impl Iterator<Item = &str> {
+        let columns = match self {
+            UntaggedValue::Row(columns) => Some(columns.keys()),
+            _ => None,
+        };
+
+        columns.into_iter().flatten().map(|key| &key[..])
+    }
+
     /// Convert this UntaggedValue to a Value with the given Tag
     pub fn into_value(self, tag: impl Into<Tag>) -> Value {
         Value {
@@ -245,6 +268,9 @@ impl Value {
     }
 
     /// View the Value as a string, if possible
+    ///
+    /// Lines are returned with their newline added back on purpose, so that the output can be written
+    /// as-is. Use `Primitive::text` to get the raw text of a String or Line instead.
     pub fn as_string(&self) -> Result<String, ShellError> {
         match &self.value {
             UntaggedValue::Primitive(Primitive::String(string)) => Ok(string.clone()),
@@ -261,6 +287,45 @@ impl Value {
         }
     }
 
+    /// View the borrowed text of a String or Line Value, without the newline of a Line. Unlike
+    /// `as_forgiving_string`, no error is built for other Values.
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            UntaggedValue::Primitive(primitive) => primitive.text(),
+            _ => None,
+        }
+    }
+
+    /// Get a textual form of any Value. Primitives are converted as `into_string_value` would, while rows,
+    /// tables and other values are described in the same way as when shown inline in a table.
+    pub fn as_lossy_string(&self) -> String {
+        match &self.value {
+            UntaggedValue::Primitive(primitive) => primitive_to_string(primitive, None),
+            UntaggedValue::Row(row) if row.entries.len() <= 6 => {
+                let columns: Vec<&str> = row.keys().map(|key| &key[..]).collect();
+                format!("[row {}]", columns.join(" "))
+            }
+            UntaggedValue::Row(row) => format!("[row {} columns]", row.entries.len()),
+            UntaggedValue::Table(table) => format!("[table {} rows]", table.len()),
+            UntaggedValue::Error(_) => "error".to_string(),
+            UntaggedValue::Block(_) => "block".to_string(),
+        }
+    }
+
+    /// Render the Value as text, leaving the formatting of each primitive to the renderer. A table is written
+    /// one element per line and a row as its `column: value` pairs. Structures nested inside a cell are written
+    /// inline, with rows in braces and tables in brackets.
+    pub fn to_text(&self, renderer: &dyn Fn(&Primitive) -> String) -> String {
+        match &self.value {
+            UntaggedValue::Table(table) => table
+                .iter()
+                .map(|value| text_of(value, renderer, false))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => text_of(self, renderer, false),
+        }
+    }
+
     /// View the Value as a path, if possible
     pub fn as_path(&self) -> Result<PathBuf, ShellError> {
         match &self.value {
@@ -270,6 +335,25 @@ impl Value {
         }
     }
 
+    /// View the Value as a column path, if possible. Strings are split on `.`, with numeric members taken as
+    /// row numbers.
+    ///
+    /// This inherent method takes precedence over `nu_value_ext::ValueExt::as_column_path`, which returns a
+    /// `Tagged<ColumnPath>` and also accepts a table of members. Code that wants the trait's behaviour must now
+    /// call it as `ValueExt::as_column_path(&value)`.
+    pub fn as_column_path(&self) -> Result<ColumnPath, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::ColumnPath(path)) => Ok(path.clone()),
+            UntaggedValue::Primitive(Primitive::String(string)) => {
+                Ok(ColumnPath::from_string(string, &self.tag))
+            }
+            _ => Err(ShellError::type_error(
+                "column path",
+                self.spanned_type_name(),
+            )),
+        }
+    }
+
     /// View the Value as a Primitive value, if possible
     pub fn as_primitive(&self) -> Result<Primitive, ShellError> {
         match &self.value {
@@ -296,6 +380,115 @@ impl Value {
             _ => Err(ShellError::type_error("boolean", self.spanned_type_name())),
         }
     }
+
+    /// Compare two Values for equality, ignoring the tags of the Values and of any Values nested inside them
+    pub fn equals_ignoring_tags(&self, other: &Value) -> bool {
+        match (&self.value, &other.value) {
+            (UntaggedValue::Row(left), UntaggedValue::Row(right)) => {
+                left.entries.len() == right.entries.len()
+                    && left
+                        .entries
+                        .iter()
+                        .all(|(key, value)| match right.entries.get(key) {
+                            Some(other) => value.equals_ignoring_tags(other),
+                            None => false,
+                        })
+            }
+            (UntaggedValue::Table(left), UntaggedValue::Table(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right.iter())
+                        .all(|(left, right)| left.equals_ignoring_tags(right))
+            }
+            (left, right) => left == right,
+        }
+    }
+
+    /// Check if the Value contains the needle. Tables contain their elements, rows contain their values, and
+    /// strings contain their substrings.
+    pub fn contains(&self, needle: &Value) -> bool {
+        match (&self.value, &needle.value) {
+            (UntaggedValue::Table(table), _) => {
+                table.iter().any(|value| value.equals_ignoring_tags(needle))
+            }
+            (UntaggedValue::Row(row), _) => {
+                row.values().any(|value| value.equals_ignoring_tags(needle))
+            }
+            (UntaggedValue::Primitive(string), UntaggedValue::Primitive(substring)) => {
+                match (string.text(), substring.text()) {
+                    (Some(string), Some(substring)) => string.contains(substring),
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Release unused capacity held by the Value's tables and rows, descending into nested values. Useful after
+    /// filtering a large table down to a few rows.
+    pub fn shrink_to_fit(&mut self) {
+        match &mut self.value {
+            UntaggedValue::Table(table) => {
+                for value in table.iter_mut() {
+                    value.shrink_to_fit();
+                }
+                table.shrink_to_fit();
+            }
+            UntaggedValue::Row(row) => {
+                for value in row.entries.values_mut() {
+                    value.shrink_to_fit();
+                }
+                row.shrink_to_fit();
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply `f` to every primitive inside the Value, descending into rows and tables. The shape of the Value
+    /// and the tags of its rows and tables are kept.
+    pub fn map_cells(
+        &self,
+        f: impl Fn(&Value) -> Result<Value, ShellError> + Copy,
+    ) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(_) => f(self),
+            UntaggedValue::Row(row) => {
+                let mut entries = IndexMap::with_capacity(row.entries.len());
+
+                for (key, value) in row.entries.iter() {
+                    entries.insert(key.clone(), value.map_cells(f)?);
+                }
+
+                Ok(UntaggedValue::row(entries).into_value(&self.tag))
+            }
+            UntaggedValue::Table(table) => {
+                let rows = table
+                    .iter()
+                    .map(|value| value.map_cells(f))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(UntaggedValue::Table(rows).into_value(&self.tag))
+            }
+            UntaggedValue::Error(_) | UntaggedValue::Block(_) => Ok(self.clone()),
+        }
+    }
+
+    /// Apply `f` to every primitive inside the Value whose type name is `type_name`, such as "date" or "string".
+    /// Other cells are left unchanged.
+    pub fn map_cells_of_type(
+        &self,
+        type_name: &str,
+        f: impl Fn(&Value) -> Result<Value, ShellError> + Copy,
+    ) -> Result<Value, ShellError> {
+        self.map_cells(|cell| {
+            if cell.type_name() == type_name {
+                f(cell)
+            } else {
+                Ok(cell.clone())
+            }
+        })
+    }
 }
 
 impl Into<Value> for String {
@@ -379,23 +572,375 @@ impl From<ShellError> for UntaggedValue {
     }
 }
 
+/// Render a primitive as plain, uncolored text. This is the renderer used when no other is given to `Value::to_text`.
+pub fn default_renderer(primitive: &Primitive) -> String {
+    format_primitive(primitive, None)
+}
+
+fn text_of(value: &Value, renderer: &dyn Fn(&Primitive) -> String, nested: bool) -> String {
+    match &value.value {
+        UntaggedValue::Primitive(primitive) => renderer(primitive),
+        UntaggedValue::Row(row) => {
+            let pairs: Vec<String> = row
+                .entries
+                .iter()
+                .map(|(column, value)| format!("{}: {}", column, text_of(value, renderer, true)))
+                .collect();
+
+            if nested {
+                format!("{{{}}}", pairs.join(", "))
+            } else {
+                pairs.join(", ")
+            }
+        }
+        UntaggedValue::Table(table) => {
+            let elements: Vec<String> = table
+                .iter()
+                .map(|value| text_of(value, renderer, true))
+                .collect();
+
+            format!("[{}]", elements.join(", "))
+        }
+        UntaggedValue::Error(_) => "error".to_string(),
+        UntaggedValue::Block(_) => "block".to_string(),
+    }
+}
+
+/// Get the columns of all the given values, in the order they are first seen. Values that aren't rows are given
+/// the `<value>` column.
 pub fn merge_descriptors(values: &[Value]) -> Vec<String> {
-    let mut ret: Vec<String> = vec![];
-    let value_column = "<value>".to_string();
+    let mut ret: IndexSet<&str> = IndexSet::new();
+
     for value in values {
-        let descs = value.data_descriptors();
+        let mut descs = value.descriptors().peekable();
 
-        if descs.is_empty() {
-            if !ret.contains(&value_column) {
-                ret.push("<value>".to_string());
-            }
+        if descs.peek().is_none() {
+            ret.insert("<value>");
         } else {
-            for desc in value.data_descriptors() {
-                if !ret.contains(&desc) {
-                    ret.push(desc);
+            ret.extend(descs);
+        }
+    }
+
+    ret.into_iter().map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Primitive, UntaggedValue, Value};
+    use crate::test_support::{int, row, string};
+    use crate::{ColumnPath, PathMember, UnspannedPathMember};
+    use indexmap::IndexMap;
+    use nu_errors::ShellError;
+    use nu_source::{Span, Tag};
+
+    fn tagged_int(input: i64, start: usize) -> Value {
+        UntaggedValue::int(input).into_value(Tag::from(Span::new(start, start + 1)))
+    }
+
+    #[test]
+    fn table_contains_its_elements() {
+        let table =
+            UntaggedValue::table(&[tagged_int(1, 0), tagged_int(2, 2)]).into_untagged_value();
+
+        assert!(table.contains(&int(2)));
+        assert!(!table.contains(&int(3)));
+    }
+
+    #[test]
+    fn row_contains_its_values() {
+        let row = row(vec![("name", string("andres")), ("age", tagged_int(32, 5))]);
+
+        assert!(row.contains(&int(32)));
+        assert!(!row.contains(&string("age")));
+    }
+
+    #[test]
+    fn string_contains_its_substrings() {
+        let string_value = string("nushell");
+
+        assert!(string_value.contains(&string("shell")));
+        assert!(!string_value.contains(&string("bash")));
+        assert!(!string_value.contains(&int(1)));
+    }
+
+    #[test]
+    fn lines_and_strings_contain_each_other() {
+        let line = |input: &str| UntaggedValue::line(input).into_untagged_value();
+
+        assert!(line("nushell").contains(&string("shell")));
+        assert!(string("nushell").contains(&line("shell")));
+        assert!(line("nushell").contains(&line("nu")));
+        assert!(!line("nushell").contains(&line("bash")));
+    }
+
+    fn double(value: &Value) -> Result<Value, ShellError> {
+        match &value.value {
+            UntaggedValue::Primitive(Primitive::Int(int)) => {
+                Ok(UntaggedValue::int(int + int).into_value(&value.tag))
+            }
+            _ => Ok(value.clone()),
+        }
+    }
+
+    #[test]
+    fn map_cells_doubles_every_integer_in_a_nested_table() -> Result<(), ShellError> {
+        let table = UntaggedValue::table(&[
+            row(vec![
+                ("name", string("a")),
+                (
+                    "sizes",
+                    UntaggedValue::table(&[int(1), int(2)]).into_untagged_value(),
+                ),
+            ]),
+            row(vec![
+                ("name", string("b")),
+                (
+                    "sizes",
+                    UntaggedValue::table(&[int(3)]).into_untagged_value(),
+                ),
+            ]),
+        ])
+        .into_value(Tag::from(Span::new(0, 10)));
+
+        let doubled = table.map_cells(double)?;
+
+        let expected = UntaggedValue::table(&[
+            row(vec![
+                ("name", string("a")),
+                (
+                    "sizes",
+                    UntaggedValue::table(&[int(2), int(4)]).into_untagged_value(),
+                ),
+            ]),
+            row(vec![
+                ("name", string("b")),
+                (
+                    "sizes",
+                    UntaggedValue::table(&[int(6)]).into_untagged_value(),
+                ),
+            ]),
+        ])
+        .into_untagged_value();
+
+        assert!(doubled.equals_ignoring_tags(&expected));
+        assert_eq!(doubled.tag, table.tag);
+
+        Ok(())
+    }
+
+    fn shout(value: &Value) -> Result<Value, ShellError> {
+        Ok(
+            UntaggedValue::string(value.as_forgiving_string()?.to_uppercase())
+                .into_value(&value.tag),
+        )
+    }
+
+    #[test]
+    fn map_cells_of_type_only_touches_matching_cells() -> Result<(), ShellError> {
+        let table = UntaggedValue::table(&[
+            row(vec![("name", string("nu")), ("age", int(1))]),
+            row(vec![("name", string("bash")), ("age", int(30))]),
+        ])
+        .into_untagged_value();
+
+        let shouted = table.map_cells_of_type("string", shout)?;
+
+        let expected = UntaggedValue::table(&[
+            row(vec![("name", string("NU")), ("age", int(1))]),
+            row(vec![("name", string("BASH")), ("age", int(30))]),
+        ])
+        .into_untagged_value();
+
+        assert!(shouted.equals_ignoring_tags(&expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn descriptors_borrow_the_columns_of_a_row() {
+        let row = row(vec![("name", string("nu")), ("size", int(1))]);
+
+        assert_eq!(row.descriptors().collect::<Vec<_>>(), vec!["name", "size"]);
+        assert_eq!(int(1).descriptors().count(), 0);
+    }
+
+    #[test]
+    fn merge_descriptors_keeps_first_seen_order() {
+        let values = vec![
+            row(vec![("name", string("nu")), ("size", int(1))]),
+            int(2),
+            row(vec![("lang", string("rust")), ("name", string("nu"))]),
+            string("three"),
+        ];
+
+        assert_eq!(
+            super::merge_descriptors(&values),
+            vec!["name", "size", "<value>", "lang"]
+        );
+    }
+
+    #[test]
+    fn merge_descriptors_of_a_wide_table() {
+        let columns: Vec<String> = (0..1000).map(|i| format!("column{}", i)).collect();
+
+        let values: Vec<Value> = (0..10)
+            .map(|offset| {
+                let mut map = IndexMap::new();
+                for column in columns.iter().skip(offset * 100) {
+                    map.insert(column.clone(), int(offset as i64));
+                }
+                for column in columns.iter().take(offset * 100) {
+                    map.insert(column.clone(), int(offset as i64));
+                }
+
+                UntaggedValue::row(map).into_untagged_value()
+            })
+            .collect();
+
+        assert_eq!(super::merge_descriptors(&values), columns);
+    }
+
+    #[test]
+    fn shrink_to_fit_releases_capacity_of_a_truncated_table() {
+        let mut nested: Vec<Value> = (0..1000).map(int).collect();
+        nested.truncate(2);
+
+        let mut rows: Vec<Value> = (0..1000)
+            .map(|_| {
+                row(vec![(
+                    "nested",
+                    UntaggedValue::Table(nested.clone()).into_untagged_value(),
+                )])
+            })
+            .collect();
+        rows.truncate(10);
+
+        let mut value = UntaggedValue::Table(rows).into_untagged_value();
+        if let UntaggedValue::Table(rows) = &mut value.value {
+            if let UntaggedValue::Row(row) = &mut rows[0].value {
+                if let Some(UntaggedValue::Table(nested)) =
+                    row.entries.get_mut("nested").map(|value| &mut value.value)
+                {
+                    nested.reserve(1000);
                 }
             }
         }
+
+        value.shrink_to_fit();
+
+        match &value.value {
+            UntaggedValue::Table(rows) => {
+                assert!(rows.capacity() < 1000);
+                assert_eq!(rows.len(), 10);
+
+                match &rows[0].value {
+                    UntaggedValue::Row(row) => match &row.entries["nested"].value {
+                        UntaggedValue::Table(nested) => {
+                            assert!(nested.capacity() < 1000);
+                            assert_eq!(nested.len(), 2);
+                        }
+                        _ => panic!("expected a nested table"),
+                    },
+                    _ => panic!("expected a row"),
+                }
+            }
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn as_str_of_strings_and_lines() {
+        assert_eq!(string("nu").as_str(), Some("nu"));
+        assert_eq!(
+            UntaggedValue::line("nu").into_untagged_value().as_str(),
+            Some("nu")
+        );
+    }
+
+    #[test]
+    fn as_str_of_non_strings() {
+        assert_eq!(int(1).as_str(), None);
+        assert_eq!(row(vec![("name", string("nu"))]).as_str(), None);
+    }
+
+    #[test]
+    fn lossy_strings_of_primitives_and_structures() {
+        assert_eq!(int(10).as_lossy_string(), "10");
+        assert_eq!(
+            UntaggedValue::boolean(true)
+                .into_untagged_value()
+                .as_lossy_string(),
+            "true"
+        );
+        assert_eq!(
+            row(vec![("name", string("nu")), ("age", int(1))]).as_lossy_string(),
+            "[row name age]"
+        );
+        assert_eq!(
+            UntaggedValue::table(&[int(1), int(2)])
+                .into_untagged_value()
+                .as_lossy_string(),
+            "[table 2 rows]"
+        );
+    }
+
+    fn shouting_renderer(primitive: &Primitive) -> String {
+        super::default_renderer(primitive).to_uppercase()
+    }
+
+    #[test]
+    fn to_text_with_a_custom_renderer() {
+        let table = UntaggedValue::table(&[
+            row(vec![("name", string("andres")), ("size", int(1))]),
+            row(vec![
+                ("name", string("jonathan")),
+                (
+                    "langs",
+                    UntaggedValue::table(&[string("rust"), string("ts")]).into_untagged_value(),
+                ),
+            ]),
+            string("done"),
+        ])
+        .into_untagged_value();
+
+        assert_eq!(
+            table.to_text(&shouting_renderer),
+            "name: ANDRES, size: 1\nname: JONATHAN, langs: [RUST, TS]\nDONE"
+        );
+        assert_eq!(
+            row(vec![("inner", row(vec![("name", string("nu"))]))])
+                .to_text(&super::default_renderer),
+            "inner: {name: nu}"
+        );
+    }
+
+    #[test]
+    fn as_column_path_of_a_column_path_value() -> Result<(), ShellError> {
+        let path = ColumnPath::new(vec![PathMember::string("name", Span::unknown())]);
+        let value =
+            UntaggedValue::Primitive(Primitive::ColumnPath(path.clone())).into_untagged_value();
+
+        assert_eq!(value.as_column_path()?, path);
+
+        Ok(())
+    }
+
+    #[test]
+    fn as_column_path_of_a_dotted_string() -> Result<(), ShellError> {
+        let path = string("a.b.c").as_column_path()?;
+
+        assert_eq!(
+            path.iter()
+                .map(|member| member.unspanned.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                UnspannedPathMember::String("a".to_string()),
+                UnspannedPathMember::String("b".to_string()),
+                UnspannedPathMember::String("c".to_string()),
+            ]
+        );
+        assert!(int(1).as_column_path().is_err());
+
+        Ok(())
     }
-    ret
 }
@@ -0,0 +1,273 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::primitive::Primitive;
+use crate::value::range::Range;
+use crate::value::{UntaggedValue, Value};
+use nu_errors::ShellError;
+
+impl UntaggedValue {
+    /// Join binary values into a single buffer
+    pub fn binary_concat(values: &[Value]) -> Result<UntaggedValue, ShellError> {
+        let mut buffer = vec![];
+
+        for value in values {
+            buffer.extend_from_slice(value.expect_binary()?);
+        }
+
+        Ok(UntaggedValue::binary(buffer))
+    }
+}
+
+impl Value {
+    /// Slice a binary value by byte indices. Negative indices count from the end of the buffer and out-of-range
+    /// bounds are clamped to the buffer
+    pub fn binary_slice(&self, range: &Range) -> Result<Value, ShellError> {
+        let bytes = self.expect_binary()?;
+        let (start, end) = range.indices_for_length(bytes.len())?;
+
+        Ok(UntaggedValue::binary(bytes[start..end].to_vec()).into_value(&self.tag))
+    }
+
+    /// Find the offset of the first occurrence of `needle` in a binary value. An empty needle is found at the start.
+    pub fn binary_index_of(&self, needle: &[u8]) -> Result<Option<usize>, ShellError> {
+        Ok(index_of(self.expect_binary()?, needle))
+    }
+
+    /// Replace the first occurrence of `find` in a binary value, or every occurrence if `all` is set. An empty
+    /// `find` leaves the value unchanged.
+    pub fn binary_replace(
+        &self,
+        find: &[u8],
+        replace: &[u8],
+        all: bool,
+    ) -> Result<Value, ShellError> {
+        let bytes = self.expect_binary()?;
+
+        if find.is_empty() {
+            return Ok(self.clone());
+        }
+
+        let mut replaced = Vec::with_capacity(bytes.len());
+        let mut rest = bytes;
+
+        while let Some(offset) = index_of(rest, find) {
+            replaced.extend_from_slice(&rest[..offset]);
+            replaced.extend_from_slice(replace);
+            rest = &rest[offset + find.len()..];
+
+            if !all {
+                break;
+            }
+        }
+
+        replaced.extend_from_slice(rest);
+
+        Ok(UntaggedValue::binary(replaced).into_value(&self.tag))
+    }
+
+    /// Reverse the bytes of a binary value
+    pub fn binary_reverse(&self) -> Result<Value, ShellError> {
+        let mut bytes = self.expect_binary()?.to_vec();
+        bytes.reverse();
+
+        Ok(UntaggedValue::binary(bytes).into_value(&self.tag))
+    }
+
+    /// Pad a binary value with `byte` up to the given width, on the left or the right. Values already at least
+    /// as wide are left unchanged.
+    pub fn binary_pad(&self, width: usize, byte: u8, left: bool) -> Result<Value, ShellError> {
+        let bytes = self.expect_binary()?;
+        let padding = vec![byte; width.saturating_sub(bytes.len())];
+
+        let padded = if left {
+            [&padding[..], bytes].concat()
+        } else {
+            [bytes, &padding[..]].concat()
+        };
+
+        Ok(UntaggedValue::binary(padded).into_value(&self.tag))
+    }
+
+    fn expect_binary(&self) -> Result<&[u8], ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Binary(bytes)) => Ok(&bytes[..]),
+            _ => Err(ShellError::type_error("binary", self.spanned_type_name())),
+        }
+    }
+}
+
+fn index_of(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::binary;
+    use crate::{Primitive, Range, RangeInclusion, UntaggedValue};
+    use nu_errors::ShellError;
+    use nu_source::SpannedItem;
+
+    fn range(from: i64, to: i64, to_inclusion: RangeInclusion) -> Range {
+        Range::new(
+            (
+                Primitive::Int(from.into()).spanned_unknown(),
+                RangeInclusion::Inclusive,
+            ),
+            (Primitive::Int(to.into()).spanned_unknown(), to_inclusion),
+        )
+    }
+
+    #[test]
+    fn binary_slice_takes_the_middle() -> Result<(), ShellError> {
+        let value = binary(&[0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(
+            value.binary_slice(&range(1, 3, RangeInclusion::Inclusive))?,
+            binary(&[1, 2, 3])
+        );
+        assert_eq!(
+            value.binary_slice(&range(1, 3, RangeInclusion::Exclusive))?,
+            binary(&[1, 2])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_slice_clamps_out_of_range_bounds() -> Result<(), ShellError> {
+        let value = binary(&[0, 1, 2, 3]);
+
+        assert_eq!(
+            value.binary_slice(&range(2, 10, RangeInclusion::Inclusive))?,
+            binary(&[2, 3])
+        );
+        assert_eq!(
+            value.binary_slice(&range(6, 10, RangeInclusion::Inclusive))?,
+            binary(&[])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_concat_joins_buffers() -> Result<(), ShellError> {
+        let values = vec![binary(&[1, 2]), binary(&[]), binary(&[3, 4, 5])];
+
+        assert_eq!(
+            UntaggedValue::binary_concat(&values)?,
+            UntaggedValue::binary(vec![1, 2, 3, 4, 5])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_concat_errors_on_non_binary() {
+        let values = vec![binary(&[1]), UntaggedValue::int(2).into_untagged_value()];
+
+        assert!(UntaggedValue::binary_concat(&values).is_err());
+    }
+
+    #[test]
+    fn binary_index_of_finds_first_occurrence() -> Result<(), ShellError> {
+        let value = binary(&[0, 1, 2, 1, 2]);
+
+        assert_eq!(value.binary_index_of(&[1, 2])?, Some(1));
+        assert_eq!(value.binary_index_of(&[2])?, Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_index_of_missing_pattern() -> Result<(), ShellError> {
+        let value = binary(&[0, 1, 2]);
+
+        assert_eq!(value.binary_index_of(&[2, 1])?, None);
+        assert_eq!(value.binary_index_of(&[0, 1, 2, 3])?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_index_of_empty_needle() -> Result<(), ShellError> {
+        assert_eq!(binary(&[0, 1]).binary_index_of(&[])?, Some(0));
+        assert_eq!(binary(&[]).binary_index_of(&[])?, Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_replace_first_occurrence() -> Result<(), ShellError> {
+        let value = binary(&[1, 2, 0, 1, 2]);
+
+        assert_eq!(
+            value.binary_replace(&[1, 2], &[9], false)?,
+            binary(&[9, 0, 1, 2])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_replace_all_occurrences() -> Result<(), ShellError> {
+        let value = binary(&[1, 2, 0, 1, 2]);
+
+        assert_eq!(
+            value.binary_replace(&[1, 2], &[7, 8, 9], true)?,
+            binary(&[7, 8, 9, 0, 7, 8, 9])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_replace_find_longer_than_buffer() -> Result<(), ShellError> {
+        let value = binary(&[1, 2]);
+
+        assert_eq!(value.binary_replace(&[1, 2, 3], &[9], true)?, value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_reverse_reverses_bytes() -> Result<(), ShellError> {
+        assert_eq!(binary(&[1, 2, 3]).binary_reverse()?, binary(&[3, 2, 1]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_pad_on_the_left() -> Result<(), ShellError> {
+        assert_eq!(
+            binary(&[1, 2]).binary_pad(4, 0, true)?,
+            binary(&[0, 0, 1, 2])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_pad_on_the_right() -> Result<(), ShellError> {
+        assert_eq!(
+            binary(&[1, 2]).binary_pad(4, 0xff, false)?,
+            binary(&[1, 2, 0xff, 0xff])
+        );
+        assert_eq!(binary(&[1, 2]).binary_pad(1, 0, false)?, binary(&[1, 2]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_slice_errors_on_non_binary() {
+        let value = UntaggedValue::string("nushell").into_untagged_value();
+
+        assert!(value
+            .binary_slice(&range(0, 1, RangeInclusion::Inclusive))
+            .is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::type_name::ShellTypeName;
+use crate::value::primitive::Primitive;
+use crate::value::UntaggedValue;
+use nu_errors::{ExpectedRange, ShellError};
+use nu_source::SpannedItem;
+use num_bigint::BigInt;
+use num_traits::cast::ToPrimitive;
+
+/// Shift amounts must be below this many bits, so that shifting left can't build an integer too large to fit in
+/// memory
+const MAX_SHIFT: usize = 1 << 16;
+
+impl UntaggedValue {
+    /// Bitwise AND of two integer values
+    pub fn bit_and(&self, other: &UntaggedValue) -> Result<UntaggedValue, ShellError> {
+        let (left, right) = (self.expect_int_operand()?, other.expect_int_operand()?);
+
+        Ok(UntaggedValue::int(left & right))
+    }
+
+    /// Bitwise OR of two integer values
+    pub fn bit_or(&self, other: &UntaggedValue) -> Result<UntaggedValue, ShellError> {
+        let (left, right) = (self.expect_int_operand()?, other.expect_int_operand()?);
+
+        Ok(UntaggedValue::int(left | right))
+    }
+
+    /// Bitwise XOR of two integer values
+    pub fn bit_xor(&self, other: &UntaggedValue) -> Result<UntaggedValue, ShellError> {
+        let (left, right) = (self.expect_int_operand()?, other.expect_int_operand()?);
+
+        Ok(UntaggedValue::int(left ^ right))
+    }
+
+    /// Shift an integer value left by the given number of bits
+    pub fn shift_left(&self, bits: &UntaggedValue) -> Result<UntaggedValue, ShellError> {
+        let (int, bits) = (self.expect_int_operand()?, bits.expect_shift_amount()?);
+
+        Ok(UntaggedValue::int(int << bits))
+    }
+
+    /// Shift an integer value right by the given number of bits
+    pub fn shift_right(&self, bits: &UntaggedValue) -> Result<UntaggedValue, ShellError> {
+        let (int, bits) = (self.expect_int_operand()?, bits.expect_shift_amount()?);
+
+        Ok(UntaggedValue::int(int >> bits))
+    }
+
+    fn expect_int_operand(&self) -> Result<&BigInt, ShellError> {
+        match self {
+            UntaggedValue::Primitive(Primitive::Int(int)) => Ok(int),
+            other => Err(ShellError::type_error(
+                "integer",
+                other.type_name().spanned_unknown(),
+            )),
+        }
+    }
+
+    fn expect_shift_amount(&self) -> Result<usize, ShellError> {
+        let int = self.expect_int_operand()?;
+
+        match int.to_usize() {
+            Some(bits) if bits < MAX_SHIFT => Ok(bits),
+            _ => Err(ShellError::range_error(
+                ExpectedRange::from(0..MAX_SHIFT),
+                &format!("{}", int).spanned_unknown(),
+                "shifting an integer by a number of bits",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UntaggedValue;
+    use nu_errors::ShellError;
+
+    fn int(input: i64) -> UntaggedValue {
+        UntaggedValue::int(input)
+    }
+
+    #[test]
+    fn bit_and() -> Result<(), ShellError> {
+        assert_eq!(int(0b1100).bit_and(&int(0b1010))?, int(0b1000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bit_or() -> Result<(), ShellError> {
+        assert_eq!(int(0b1100).bit_or(&int(0b1010))?, int(0b1110));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bit_xor() -> Result<(), ShellError> {
+        assert_eq!(int(0b1100).bit_xor(&int(0b1010))?, int(0b0110));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shift_left() -> Result<(), ShellError> {
+        assert_eq!(int(3).shift_left(&int(4))?, int(48));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shift_right() -> Result<(), ShellError> {
+        assert_eq!(int(48).shift_right(&int(4))?, int(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bits_error_on_invalid_operands() {
+        assert!(int(1).bit_and(&UntaggedValue::string("1")).is_err());
+        assert!(UntaggedValue::boolean(true).bit_or(&int(1)).is_err());
+        assert!(int(1).shift_left(&int(-1)).is_err());
+    }
+
+    #[test]
+    fn shifts_are_limited_in_size() {
+        assert!(int(1).shift_left(&int(65535)).is_ok());
+        assert!(int(1).shift_left(&int(65536)).is_err());
+        assert!(int(1).shift_left(&int(1_000_000_000_000_000)).is_err());
+        assert!(int(1).shift_right(&int(1_000_000_000_000_000)).is_err());
+    }
+}
@@ -0,0 +1,575 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::dict::{Dictionary, TaggedDictBuilder};
+use crate::value::primitive::{format_duration, format_primitive, Primitive};
+use crate::value::{UntaggedValue, Value};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use nu_errors::{ExpectedRange, ShellError};
+use nu_source::{SpannedItem, Tag};
+use num_bigint::BigInt;
+use num_traits::cast::ToPrimitive;
+use std::str::FromStr;
+
+#[allow(clippy::wrong_self_convention)]
+impl Value {
+    /// Coerce the Value into an integer, if possible. Strings and lines are parsed, booleans become 1 or 0 and
+    /// filesizes become their number of bytes.
+    ///
+    /// Decimals are truncated toward zero, so both 1.9 and 1.1 become 1, and -1.9 becomes -1.
+    pub fn into_int(&self) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Int(_)) => Ok(self.clone()),
+            UntaggedValue::Primitive(primitive) if primitive.text().is_some() => self.parse_int(0),
+            UntaggedValue::Primitive(Primitive::Decimal(decimal)) => {
+                let (int, _) = decimal.with_scale(0).as_bigint_and_exponent();
+                Ok(UntaggedValue::int(int).into_value(&self.tag))
+            }
+            UntaggedValue::Primitive(Primitive::Boolean(boolean)) => {
+                Ok(UntaggedValue::int(if *boolean { 1 } else { 0 }).into_value(&self.tag))
+            }
+            UntaggedValue::Primitive(Primitive::Bytes(bytes)) => {
+                Ok(UntaggedValue::int(*bytes).into_value(&self.tag))
+            }
+            _ => Err(ShellError::type_error("integer", self.spanned_type_name())),
+        }
+    }
+
+    /// Coerce the Value into a decimal, if possible. Strings and lines are parsed and booleans become 1 or 0
+    pub fn into_decimal(&self) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Decimal(_)) => Ok(self.clone()),
+            UntaggedValue::Primitive(Primitive::Int(int)) => {
+                Ok(UntaggedValue::decimal(BigDecimal::new(int.clone(), 0)).into_value(&self.tag))
+            }
+            UntaggedValue::Primitive(primitive) if primitive.text().is_some() => self.parse_float(),
+            UntaggedValue::Primitive(Primitive::Boolean(boolean)) => {
+                let decimal = BigDecimal::new(BigInt::from(if *boolean { 1 } else { 0 }), 0);
+                Ok(UntaggedValue::decimal(decimal).into_value(&self.tag))
+            }
+            _ => Err(ShellError::type_error("decimal", self.spanned_type_name())),
+        }
+    }
+
+    /// Coerce a primitive Value into a string. Numbers can optionally be formatted with a fixed number of
+    /// decimal places. Dates are formatted as ISO-8601 and durations in their humanized form
+    pub fn into_string_value(&self, decimals: Option<usize>) -> Result<Value, ShellError> {
+        let primitive = match &self.value {
+            UntaggedValue::Primitive(primitive) => primitive,
+            _ => {
+                return Err(ShellError::type_error(
+                    "primitive",
+                    self.spanned_type_name(),
+                ))
+            }
+        };
+
+        Ok(UntaggedValue::string(primitive_to_string(primitive, decimals)).into_value(&self.tag))
+    }
+
+    /// Coerce the Value into a filesize, if possible. Integers are taken as a number of bytes, and strings and lines
+    /// are parsed as a size with an optional decimal (eg `kb`, `mb`) or binary (eg `kib`, `mib`) unit
+    pub fn into_filesize(&self) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Bytes(_)) => Ok(self.clone()),
+            UntaggedValue::Primitive(Primitive::Int(_)) => {
+                Ok(UntaggedValue::bytes(self.as_u64()?).into_value(&self.tag))
+            }
+            UntaggedValue::Primitive(primitive) => match primitive.text() {
+                Some(text) => Ok(
+                    UntaggedValue::bytes(parse_filesize(text, &self.tag)?).into_value(&self.tag)
+                ),
+                None => Err(ShellError::type_error("filesize", self.spanned_type_name())),
+            },
+            _ => Err(ShellError::type_error("filesize", self.spanned_type_name())),
+        }
+    }
+
+    /// Coerce the Value into a date, if possible. Strings and lines are parsed with the given strftime format, or as
+    /// RFC3339 if no format is given, and integers are taken as a Unix timestamp in seconds. A format with no
+    /// time, like `%Y-%m-%d`, gives midnight UTC on that day.
+    pub fn into_datetime(&self, format: Option<&str>) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Date(_)) => Ok(self.clone()),
+            UntaggedValue::Primitive(Primitive::Int(int)) => {
+                match int
+                    .to_i64()
+                    .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+                {
+                    Some(date) => Ok(UntaggedValue::date(date).into_value(&self.tag)),
+                    None => Err(ShellError::range_error(
+                        ExpectedRange::I64,
+                        &format!("{}", int).spanned(self.tag.span),
+                        "converting a timestamp into a date",
+                    )),
+                }
+            }
+            UntaggedValue::Primitive(primitive) => match primitive.text() {
+                Some(text) => Ok(
+                    UntaggedValue::date(parse_datetime(text, format, &self.tag)?)
+                        .into_value(&self.tag),
+                ),
+                None => Err(ShellError::type_error("date", self.spanned_type_name())),
+            },
+            _ => Err(ShellError::type_error("date", self.spanned_type_name())),
+        }
+    }
+
+    /// Coerce the Value into binary, if possible. Strings become their UTF-8 bytes, and non-negative integers
+    /// become their big-endian bytes with no leading zero bytes
+    pub fn into_binary(&self) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Binary(_)) => Ok(self.clone()),
+            UntaggedValue::Primitive(Primitive::String(string)) => {
+                Ok(UntaggedValue::binary(string.as_bytes().to_vec()).into_value(&self.tag))
+            }
+            UntaggedValue::Primitive(Primitive::Int(int)) => match int.to_biguint() {
+                Some(uint) => Ok(UntaggedValue::binary(uint.to_bytes_be()).into_value(&self.tag)),
+                None => Err(ShellError::range_error(
+                    ExpectedRange::BigInt,
+                    &format!("{}", int).spanned(self.tag.span),
+                    "converting a negative integer into binary",
+                )),
+            },
+            _ => Err(ShellError::type_error("binary", self.spanned_type_name())),
+        }
+    }
+
+    /// Get the record (row) of the Value. A table with exactly one row is unwrapped into that row.
+    pub fn into_record(&self) -> Result<Dictionary, ShellError> {
+        match &self.value {
+            UntaggedValue::Row(dict) => Ok(dict.clone()),
+            UntaggedValue::Table(table) if table.len() == 1 => table[0].into_record(),
+            UntaggedValue::Table(table) => Err(ShellError::labeled_error(
+                "Expected a single row",
+                format!("table has {} rows", table.len()),
+                &self.tag,
+            )),
+            _ => Err(ShellError::type_error("row", self.spanned_type_name())),
+        }
+    }
+
+    /// Get the Value as a table. Rows become a single-row table, tables are left unchanged and any other value
+    /// becomes a single-row table holding the value in a `<value>` column.
+    pub fn into_table(&self) -> UntaggedValue {
+        match &self.value {
+            UntaggedValue::Table(_) => self.value.clone(),
+            UntaggedValue::Row(_) => UntaggedValue::Table(vec![self.clone()]),
+            _ => {
+                let mut row = TaggedDictBuilder::new(&self.tag);
+                row.insert_value("<value>", self.clone());
+                UntaggedValue::Table(vec![row.into_value()])
+            }
+        }
+    }
+}
+
+/// Convert a primitive into plain text, unlike `format_primitive` which formats it for display
+pub(crate) fn primitive_to_string(primitive: &Primitive, decimals: Option<usize>) -> String {
+    match (primitive, decimals) {
+        (Primitive::Int(int), Some(decimals)) => {
+            format!("{:.*}", decimals, BigDecimal::new(int.clone(), 0))
+        }
+        (Primitive::Int(int), None) => int.to_string(),
+        (Primitive::Decimal(decimal), Some(decimals)) => format!("{:.*}", decimals, decimal),
+        (Primitive::Decimal(decimal), None) => decimal.to_string(),
+        (Primitive::Boolean(boolean), _) => boolean.to_string(),
+        (Primitive::Date(date), _) => date.to_rfc3339(),
+        (Primitive::Duration(secs), _) => format_duration(*secs),
+        (other, _) => format_primitive(other, None),
+    }
+}
+
+fn parse_datetime(
+    input: &str,
+    format: Option<&str>,
+    tag: &Tag,
+) -> Result<DateTime<Utc>, ShellError> {
+    let date = match format {
+        Some(format) => DateTime::parse_from_str(input, format)
+            .map(|date| date.with_timezone(&Utc))
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(input, format)
+                    .or_else(|_| {
+                        NaiveDate::parse_from_str(input, format).map(|date| date.and_hms(0, 0, 0))
+                    })
+                    .map(|date| DateTime::<Utc>::from_utc(date, Utc))
+            }),
+        None => DateTime::parse_from_rfc3339(input).map(|date| date.with_timezone(&Utc)),
+    };
+
+    date.map_err(|reason| {
+        ShellError::labeled_error("Could not parse as date", reason.to_string(), tag)
+    })
+}
+
+fn parse_filesize(input: &str, tag: &Tag) -> Result<u64, ShellError> {
+    let input = input.trim();
+    let split_at = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or_else(|| input.len());
+    let (number, unit) = input.split_at(split_at);
+
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1000,
+        "mb" => 1000u64.pow(2),
+        "gb" => 1000u64.pow(3),
+        "tb" => 1000u64.pow(4),
+        "pb" => 1000u64.pow(5),
+        "kib" => 1024,
+        "mib" => 1024u64.pow(2),
+        "gib" => 1024u64.pow(3),
+        "tib" => 1024u64.pow(4),
+        "pib" => 1024u64.pow(5),
+        other => {
+            return Err(ShellError::labeled_error(
+                "Unknown filesize unit",
+                format!("'{}' is not a filesize unit", other),
+                tag,
+            ))
+        }
+    };
+
+    let number = BigDecimal::from_str(number).map_err(|_| {
+        ShellError::labeled_error(
+            "Could not parse as filesize",
+            format!("'{}' is not a filesize", input),
+            tag,
+        )
+    })?;
+
+    let (bytes, _) = (number * BigDecimal::new(BigInt::from(multiplier), 0))
+        .with_scale(0)
+        .as_bigint_and_exponent();
+
+    bytes.to_u64().ok_or_else(|| {
+        ShellError::range_error(
+            ExpectedRange::U64,
+            &format!("{}", bytes).spanned(tag.span),
+            "converting a size into a filesize",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{binary, decimal, int, line, row, string};
+    use crate::{Dictionary, UntaggedValue, Value};
+    use chrono::DateTime;
+    use nu_errors::ShellError;
+
+    #[test]
+    fn into_int_from_string() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::string("42")
+                .into_untagged_value()
+                .into_int()?,
+            int(42)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn lines_coerce_like_strings() -> Result<(), ShellError> {
+        assert_eq!(line("42").into_int()?, int(42));
+        assert_eq!(line("1.5").into_decimal()?, decimal("1.5"));
+        assert_eq!(line("10kb").into_filesize()?, filesize(10_000));
+        assert_eq!(
+            line("2020-02-14T06:30:00+00:00").into_datetime(None)?,
+            string("2020-02-14T06:30:00+00:00").into_datetime(None)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_int_truncates_decimals() -> Result<(), ShellError> {
+        assert_eq!(decimal("1.9").into_int()?, int(1));
+        assert_eq!(decimal("-1.9").into_int()?, int(-1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_int_from_boolean() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::boolean(true)
+                .into_untagged_value()
+                .into_int()?,
+            int(1)
+        );
+        assert_eq!(
+            UntaggedValue::boolean(false)
+                .into_untagged_value()
+                .into_int()?,
+            int(0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_int_from_filesize() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::bytes(1024u64)
+                .into_untagged_value()
+                .into_int()?,
+            int(1024)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_int_from_int() -> Result<(), ShellError> {
+        assert_eq!(int(7).into_int()?, int(7));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_decimal_from_int() -> Result<(), ShellError> {
+        assert_eq!(int(42).into_decimal()?, decimal("42"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_decimal_from_string() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::string("3.25")
+                .into_untagged_value()
+                .into_decimal()?,
+            decimal("3.25")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_decimal_from_boolean() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::boolean(true)
+                .into_untagged_value()
+                .into_decimal()?,
+            decimal("1")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_decimal_errors_on_invalid_string() {
+        assert!(UntaggedValue::string("nu")
+            .into_untagged_value()
+            .into_decimal()
+            .is_err());
+    }
+
+    #[test]
+    fn into_string_from_decimal() -> Result<(), ShellError> {
+        assert_eq!(decimal("3.25").into_string_value(None)?, string("3.25"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_string_from_decimal_with_fixed_decimals() -> Result<(), ShellError> {
+        assert_eq!(
+            decimal("3.25").into_string_value(Some(4))?,
+            string("3.2500")
+        );
+        assert_eq!(int(3).into_string_value(Some(2))?, string("3.00"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_string_from_other_primitives() -> Result<(), ShellError> {
+        assert_eq!(int(42).into_string_value(None)?, string("42"));
+        assert_eq!(
+            UntaggedValue::boolean(true)
+                .into_untagged_value()
+                .into_string_value(None)?,
+            string("true")
+        );
+        assert!(UntaggedValue::table(&[])
+            .into_untagged_value()
+            .into_string_value(None)
+            .is_err());
+
+        Ok(())
+    }
+
+    fn filesize(input: u64) -> Value {
+        UntaggedValue::bytes(input).into_untagged_value()
+    }
+
+    #[test]
+    fn into_filesize_from_string() -> Result<(), ShellError> {
+        assert_eq!(string("10kb").into_filesize()?, filesize(10_000));
+        assert_eq!(string("10 KiB").into_filesize()?, filesize(10_240));
+        assert_eq!(string("1.5mb").into_filesize()?, filesize(1_500_000));
+        assert_eq!(string("512").into_filesize()?, filesize(512));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_filesize_from_int() -> Result<(), ShellError> {
+        assert_eq!(int(2048).into_filesize()?, filesize(2048));
+        assert!(int(-1).into_filesize().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_filesize_errors_on_unknown_suffix() {
+        assert!(string("10zb").into_filesize().is_err());
+        assert!(string("kb").into_filesize().is_err());
+    }
+
+    fn date(input: &str) -> Value {
+        UntaggedValue::date(DateTime::parse_from_rfc3339(input).unwrap()).into_untagged_value()
+    }
+
+    #[test]
+    fn into_datetime_from_rfc3339() -> Result<(), ShellError> {
+        assert_eq!(
+            string("2020-02-14T08:30:00+02:00").into_datetime(None)?,
+            date("2020-02-14T06:30:00+00:00")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_datetime_with_format() -> Result<(), ShellError> {
+        assert_eq!(
+            string("14/02/2020 06:30").into_datetime(Some("%d/%m/%Y %H:%M"))?,
+            date("2020-02-14T06:30:00+00:00")
+        );
+        assert!(string("2020-02-14")
+            .into_datetime(Some("%d/%m/%Y %H:%M"))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_datetime_with_a_date_only_format() -> Result<(), ShellError> {
+        assert_eq!(
+            string("2020-02-14").into_datetime(Some("%Y-%m-%d"))?,
+            date("2020-02-14T00:00:00+00:00")
+        );
+        assert!(string("2020-02-30")
+            .into_datetime(Some("%Y-%m-%d"))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_datetime_from_timestamp() -> Result<(), ShellError> {
+        assert_eq!(
+            int(1_581_661_800).into_datetime(None)?,
+            date("2020-02-14T06:30:00+00:00")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_binary_from_string() -> Result<(), ShellError> {
+        assert_eq!(string("nü").into_binary()?, binary(&[0x6e, 0xc3, 0xbc]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_binary_from_int() -> Result<(), ShellError> {
+        assert_eq!(int(258).into_binary()?, binary(&[0x01, 0x02]));
+        assert_eq!(int(255).into_binary()?, binary(&[0xff]));
+        assert_eq!(int(0).into_binary()?, binary(&[0x00]));
+        assert!(int(-1).into_binary().is_err());
+
+        Ok(())
+    }
+
+    fn dict(value: Value) -> Dictionary {
+        match value.value {
+            UntaggedValue::Row(dict) => dict,
+            _ => unreachable!("rows are dictionaries"),
+        }
+    }
+
+    #[test]
+    fn into_record_from_single_row_table() -> Result<(), ShellError> {
+        let table =
+            UntaggedValue::table(&[row(vec![("name", string("nu"))])]).into_untagged_value();
+
+        assert_eq!(
+            table.into_record()?,
+            dict(row(vec![("name", string("nu"))]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_record_from_row() -> Result<(), ShellError> {
+        let value = row(vec![("name", string("nu"))]);
+
+        assert_eq!(value.into_record()?, dict(value));
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_record_errors_on_multiple_or_no_rows() {
+        let rows = vec![
+            row(vec![("name", string("nu"))]),
+            row(vec![("name", string("bash"))]),
+        ];
+
+        assert!(UntaggedValue::table(&rows)
+            .into_untagged_value()
+            .into_record()
+            .is_err());
+        assert!(UntaggedValue::table(&[])
+            .into_untagged_value()
+            .into_record()
+            .is_err());
+    }
+
+    #[test]
+    fn into_table_wraps_a_row() {
+        let value = row(vec![("name", string("nu"))]);
+
+        assert_eq!(value.into_table(), UntaggedValue::table(&[value]));
+    }
+
+    #[test]
+    fn into_table_leaves_tables_unchanged() {
+        let value = UntaggedValue::table(&[int(1), int(2)]);
+
+        assert_eq!(value.clone().into_untagged_value().into_table(), value);
+    }
+
+    #[test]
+    fn into_table_wraps_a_primitive_in_a_value_column() {
+        assert_eq!(
+            int(1).into_table(),
+            UntaggedValue::table(&[row(vec![("<value>", int(1))])])
+        );
+    }
+
+    #[test]
+    fn into_int_errors_on_unsupported_types() {
+        assert!(UntaggedValue::nothing()
+            .into_untagged_value()
+            .into_int()
+            .is_err());
+    }
+}
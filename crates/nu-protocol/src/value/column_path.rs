@@ -1,7 +1,9 @@
 use crate::Value;
 use derive_new::new;
 use getset::Getters;
-use nu_source::{b, span_for_spanned_list, DebugDocBuilder, HasFallibleSpan, PrettyDebug, Span};
+use nu_source::{
+    b, span_for_spanned_list, DebugDocBuilder, HasFallibleSpan, PrettyDebug, Span, Tag,
+};
 use num_bigint::BigInt;
 use serde::{Deserialize, Serialize};
 
@@ -57,6 +59,27 @@ impl ColumnPath {
         self.members.iter()
     }
 
+    /// Parse a dotted path like `list.0.name` into a column path. Numeric members become row numbers and the
+    /// rest become column names. Each member's span is its position within `tag`'s span.
+    pub fn from_string(input: &str, tag: impl Into<Tag>) -> ColumnPath {
+        let start = tag.into().span.start();
+        let mut offset = start;
+        let mut members = vec![];
+
+        for member in input.split('.') {
+            let span = Span::new(offset, offset + member.len());
+
+            members.push(match member.parse::<BigInt>() {
+                Ok(index) => PathMember::int(index, span),
+                Err(_) => PathMember::string(member, span),
+            });
+
+            offset += member.len() + 1;
+        }
+
+        ColumnPath::new(members)
+    }
+
     /// Returns the last member and a slice of the remaining members
     pub fn split_last(&self) -> Option<(&PathMember, &[PathMember])> {
         self.members.split_last()
@@ -127,3 +150,34 @@ pub fn did_you_mean(obj_source: &Value, field_tried: &PathMember) -> Option<Vec<
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnPath, UnspannedPathMember};
+    use nu_source::{Span, Tag};
+
+    #[test]
+    fn from_string_detects_row_numbers_and_column_names() {
+        let path = ColumnPath::from_string("list.0.name", Tag::from(Span::new(10, 21)));
+
+        let members: Vec<_> = path
+            .iter()
+            .map(|member| (member.unspanned.clone(), member.span))
+            .collect();
+
+        assert_eq!(
+            members,
+            vec![
+                (
+                    UnspannedPathMember::String("list".to_string()),
+                    Span::new(10, 14)
+                ),
+                (UnspannedPathMember::Int(0.into()), Span::new(15, 16)),
+                (
+                    UnspannedPathMember::String("name".to_string()),
+                    Span::new(17, 21)
+                ),
+            ]
+        );
+    }
+}
@@ -1,3 +1,9 @@
+pub mod ini;
+pub mod json;
+pub mod markdown;
+mod ssv;
+pub mod xml;
+
 use crate::type_name::SpannedTypeName;
 use crate::value::dict::Dictionary;
 use crate::value::primitive::Primitive;
@@ -0,0 +1,164 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::{UntaggedValue, Value};
+use indexmap::IndexMap;
+use nu_errors::ShellError;
+use nu_source::Tag;
+
+/// Parse INI text into a row with a nested row for each section, holding that section's keys as strings
+pub fn from_ini(input: &str, tag: impl Into<Tag>) -> Result<Value, ShellError> {
+    let tag = tag.into();
+
+    let sections: IndexMap<String, IndexMap<String, String>> =
+        serde_ini::from_str(input).map_err(|err| {
+            ShellError::labeled_error("Could not parse as INI", err.to_string(), &tag)
+        })?;
+
+    let mut top = TaggedDictBuilder::new(&tag);
+
+    for (name, entries) in sections {
+        let mut section = TaggedDictBuilder::new(&tag);
+
+        for (key, value) in entries {
+            section.insert_untagged(key, UntaggedValue::string(value));
+        }
+
+        top.insert_value(name, section.into_value());
+    }
+
+    Ok(top.into_value())
+}
+
+/// Write a row of sections as INI text. Every column must be a row of primitive values. Names and values that
+/// would not read back the same, such as a key containing `=` or a value spanning several lines, are an error.
+pub fn to_ini(value: &Value) -> Result<String, ShellError> {
+    let sections = match &value.value {
+        UntaggedValue::Row(row) => row,
+        _ => return Err(ShellError::type_error("row", value.spanned_type_name())),
+    };
+
+    let mut output = String::new();
+
+    for (name, section) in sections.entries.iter() {
+        let entries = match &section.value {
+            UntaggedValue::Row(entries) => entries,
+            _ => {
+                return Err(ShellError::labeled_error(
+                    "Expected an INI section",
+                    format!("'{}' is not a row of keys and values", name),
+                    &section.tag,
+                ))
+            }
+        };
+
+        expect_ini_text(name, &[']'], "section name", &section.tag)?;
+
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&format!("[{}]\n", name));
+
+        for (key, entry) in entries.entries.iter() {
+            match &entry.value {
+                UntaggedValue::Primitive(_) => {
+                    let text = entry.as_lossy_string();
+
+                    expect_ini_text(key, &['=', '[', ']'], "key", &entry.tag)?;
+                    expect_ini_text(&text, &[], "value", &entry.tag)?;
+
+                    output.push_str(&format!("{}={}\n", key, text))
+                }
+                _ => {
+                    return Err(ShellError::labeled_error(
+                        "Cannot nest values in an INI section",
+                        format!("'{}.{}' is a {}", name, key, entry.spanned_type_name().item),
+                        &entry.tag,
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Check that text can be written on a single INI line without any of the reserved characters
+fn expect_ini_text(
+    text: &str,
+    reserved: &[char],
+    description: &str,
+    tag: &Tag,
+) -> Result<(), ShellError> {
+    match text
+        .chars()
+        .find(|c| *c == '\n' || *c == '\r' || reserved.contains(c))
+    {
+        Some(c) => Err(ShellError::labeled_error(
+            "Cannot write as INI",
+            format!("{} '{}' contains {:?}", description, text, c),
+            tag,
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_ini, to_ini};
+    use crate::test_support::{row, string};
+    use crate::UntaggedValue;
+    use nu_errors::ShellError;
+    use nu_source::Tag;
+
+    const CONFIG: &str = "[shell]\nname=nu\nversion=0.11\n\n[colors]\nheader=green\n";
+
+    #[test]
+    fn from_ini_maps_sections_to_rows() -> Result<(), ShellError> {
+        let value = from_ini(CONFIG, Tag::unknown())?;
+
+        assert!(value.equals_ignoring_tags(&row(vec![
+            (
+                "shell",
+                row(vec![("name", string("nu")), ("version", string("0.11"))])
+            ),
+            ("colors", row(vec![("header", string("green"))])),
+        ])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ini_round_trip() -> Result<(), ShellError> {
+        let value = from_ini(CONFIG, Tag::unknown())?;
+
+        assert_eq!(to_ini(&value)?, CONFIG);
+        assert!(from_ini(&to_ini(&value)?, Tag::unknown())?.equals_ignoring_tags(&value));
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_ini_errors_on_nested_values() {
+        let value = row(vec![(
+            "shell",
+            row(vec![(
+                "plugins",
+                UntaggedValue::table(&[]).into_untagged_value(),
+            )]),
+        )]);
+
+        assert!(to_ini(&value).is_err());
+        assert!(to_ini(&row(vec![("name", string("nu"))])).is_err());
+    }
+
+    #[test]
+    fn to_ini_errors_on_text_that_cannot_round_trip() {
+        let section =
+            |key: &str, value: &str| row(vec![("shell", row(vec![(key, string(value))]))]);
+
+        assert!(to_ini(&section("name", "nu\nshell")).is_err());
+        assert!(to_ini(&section("name=full", "nu")).is_err());
+        assert!(to_ini(&section("[name]", "nu")).is_err());
+        assert!(to_ini(&row(vec![("shell]", row(vec![("name", string("nu"))]))])).is_err());
+    }
+}
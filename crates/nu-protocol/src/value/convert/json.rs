@@ -0,0 +1,275 @@
+use crate::value::column_path::UnspannedPathMember;
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::primitive::Primitive;
+use crate::value::{UntaggedValue, Value};
+use nu_errors::{CoerceInto, ShellError};
+use nu_source::{Tag, TaggedItem};
+use num_traits::cast::ToPrimitive;
+use std::io::{self, Read, Write};
+
+impl Value {
+    /// Parse JSON straight from a reader, without first reading it all into a String. The reader is read a byte
+    /// at a time, so wrap unbuffered sources such as files in a `BufReader`. Parse errors report the byte offset
+    /// where parsing stopped.
+    pub fn from_json_reader<R: Read>(reader: R, tag: impl Into<Tag>) -> Result<Value, ShellError> {
+        let tag = tag.into();
+        let mut reader = CountingReader { reader, count: 0 };
+
+        let json: serde_json::Value = serde_json::from_reader(&mut reader).map_err(|err| {
+            let label = if err.is_io() {
+                err.to_string()
+            } else {
+                format!("{} (at byte {})", err, reader.count)
+            };
+
+            ShellError::labeled_error("Could not parse as JSON", label, &tag)
+        })?;
+
+        Ok(json_value_to_value(&json, &tag))
+    }
+}
+
+/// Keeps count of the bytes read through it, so that errors can point at an offset in the input
+struct CountingReader<R> {
+    reader: R,
+    count: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.count += read;
+
+        Ok(read)
+    }
+}
+
+fn json_value_to_value(json: &serde_json::Value, tag: &Tag) -> Value {
+    match json {
+        serde_json::Value::Null => UntaggedValue::nothing().into_value(tag),
+        serde_json::Value::Bool(b) => UntaggedValue::boolean(*b).into_value(tag),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+            (Some(n), _, _) => UntaggedValue::int(n).into_value(tag),
+            (_, Some(n), _) => UntaggedValue::int(n).into_value(tag),
+            (_, _, Some(n)) => UntaggedValue::decimal(n).into_value(tag),
+            _ => UntaggedValue::nothing().into_value(tag),
+        },
+        serde_json::Value::String(s) => UntaggedValue::string(s).into_value(tag),
+        serde_json::Value::Array(a) => UntaggedValue::Table(
+            a.iter()
+                .map(|value| json_value_to_value(value, tag))
+                .collect(),
+        )
+        .into_value(tag),
+        serde_json::Value::Object(o) => {
+            let mut row = TaggedDictBuilder::new(tag);
+            for (key, value) in o.iter() {
+                row.insert_value(key.clone(), json_value_to_value(value, tag));
+            }
+
+            row.into_value()
+        }
+    }
+}
+
+/// Writes Values as newline-delimited JSON, one Value per line, so that large tables can be streamed out
+/// without building the whole document in memory
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    /// Create a writer that writes lines to the given output
+    pub fn new(writer: W) -> NdjsonWriter<W> {
+        NdjsonWriter { writer }
+    }
+
+    /// Write the Value as a single line of JSON
+    pub fn push(&mut self, value: &Value) -> Result<(), ShellError> {
+        serde_json::to_writer(&mut self.writer, &value_to_json_value(value)?)?;
+        self.writer.write_all(b"\n")?;
+
+        Ok(())
+    }
+
+    /// Flush the output and give it back
+    pub fn into_inner(mut self) -> Result<W, ShellError> {
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Convert a Value into the matching JSON value. Blocks and ranges have no JSON equivalent and become null.
+pub(crate) fn value_to_json_value(value: &Value) -> Result<serde_json::Value, ShellError> {
+    Ok(match &value.value {
+        UntaggedValue::Primitive(Primitive::Boolean(b)) => serde_json::Value::Bool(*b),
+        UntaggedValue::Primitive(Primitive::Bytes(b)) => serde_json::Value::Number((*b).into()),
+        UntaggedValue::Primitive(Primitive::Duration(secs)) => {
+            serde_json::Value::Number((*secs).into())
+        }
+        UntaggedValue::Primitive(Primitive::Date(d)) => serde_json::Value::String(d.to_string()),
+        UntaggedValue::Primitive(Primitive::Decimal(d)) => {
+            match d.to_f64().and_then(serde_json::Number::from_f64) {
+                Some(number) => serde_json::Value::Number(number),
+                None => {
+                    return Err(ShellError::labeled_error(
+                        "Could not convert value to decimal number",
+                        "could not convert to decimal",
+                        &value.tag,
+                    ))
+                }
+            }
+        }
+        UntaggedValue::Primitive(Primitive::Int(i)) => serde_json::Value::Number(
+            CoerceInto::<i64>::coerce_into(i.tagged(&value.tag), "converting to JSON number")?
+                .into(),
+        ),
+        UntaggedValue::Primitive(Primitive::Nothing)
+        | UntaggedValue::Primitive(Primitive::BeginningOfStream)
+        | UntaggedValue::Primitive(Primitive::EndOfStream) => serde_json::Value::Null,
+        UntaggedValue::Primitive(Primitive::String(s))
+        | UntaggedValue::Primitive(Primitive::Line(s))
+        | UntaggedValue::Primitive(Primitive::Pattern(s)) => serde_json::Value::String(s.clone()),
+        UntaggedValue::Primitive(Primitive::ColumnPath(path)) => serde_json::Value::Array(
+            path.iter()
+                .map(|member| match &member.unspanned {
+                    UnspannedPathMember::String(string) => {
+                        Ok(serde_json::Value::String(string.clone()))
+                    }
+                    UnspannedPathMember::Int(int) => Ok(serde_json::Value::Number(
+                        CoerceInto::<i64>::coerce_into(
+                            int.tagged(&value.tag),
+                            "converting to JSON number",
+                        )?
+                        .into(),
+                    )),
+                })
+                .collect::<Result<Vec<_>, ShellError>>()?,
+        ),
+        UntaggedValue::Primitive(Primitive::Path(path)) => {
+            serde_json::Value::String(path.display().to_string())
+        }
+        UntaggedValue::Primitive(Primitive::Binary(bytes)) => serde_json::Value::Array(
+            bytes
+                .iter()
+                .map(|byte| serde_json::Value::Number((*byte).into()))
+                .collect(),
+        ),
+        UntaggedValue::Primitive(Primitive::Range(_)) | UntaggedValue::Block(_) => {
+            serde_json::Value::Null
+        }
+        UntaggedValue::Error(e) => return Err(e.clone()),
+        UntaggedValue::Table(table) => serde_json::Value::Array(
+            table
+                .iter()
+                .map(value_to_json_value)
+                .collect::<Result<Vec<_>, ShellError>>()?,
+        ),
+        UntaggedValue::Row(row) => {
+            let mut map = serde_json::Map::new();
+
+            for (key, value) in row.entries.iter() {
+                map.insert(key.clone(), value_to_json_value(value)?);
+            }
+
+            serde_json::Value::Object(map)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NdjsonWriter;
+    use crate::{UntaggedValue, Value};
+    use indexmap::IndexMap;
+    use nu_errors::ShellError;
+    use nu_source::Tag;
+    use std::io::Cursor;
+
+    fn row(entries: Vec<(&str, UntaggedValue)>) -> Value {
+        let mut map = IndexMap::new();
+
+        for (key, value) in entries {
+            map.insert(key.to_string(), value.into_untagged_value());
+        }
+
+        UntaggedValue::row(map).into_untagged_value()
+    }
+
+    #[test]
+    fn writes_one_line_per_row() -> Result<(), ShellError> {
+        let rows = vec![
+            row(vec![
+                ("name", UntaggedValue::string("nu")),
+                ("age", UntaggedValue::int(1)),
+            ]),
+            row(vec![
+                ("name", UntaggedValue::string("bash")),
+                ("age", UntaggedValue::int(30)),
+            ]),
+            row(vec![
+                ("name", UntaggedValue::string("fish")),
+                ("age", UntaggedValue::nothing()),
+            ]),
+        ];
+
+        let mut writer = NdjsonWriter::new(vec![]);
+        for row in rows.iter() {
+            writer.push(row)?;
+        }
+
+        let output = String::from_utf8(writer.into_inner()?).expect("output is valid utf-8");
+        let mut lines: Vec<serde_json::Value> = vec![];
+        for line in output.lines() {
+            lines.push(serde_json::from_str(line)?);
+        }
+
+        assert_eq!(
+            lines,
+            vec![
+                serde_json::json!({"name": "nu", "age": 1}),
+                serde_json::json!({"name": "bash", "age": 30}),
+                serde_json::json!({"name": "fish", "age": null}),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_json_from_a_reader() -> Result<(), ShellError> {
+        let input = Cursor::new(r#"{"name": "nu", "age": 1, "langs": ["rust", null]}"#);
+
+        let value = Value::from_json_reader(input, Tag::unknown())?;
+
+        assert_eq!(
+            value,
+            row(vec![
+                ("name", UntaggedValue::string("nu")),
+                ("age", UntaggedValue::int(1)),
+                (
+                    "langs",
+                    UntaggedValue::table(&[
+                        UntaggedValue::string("rust").into_untagged_value(),
+                        UntaggedValue::nothing().into_untagged_value(),
+                    ])
+                ),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_offset_of_truncated_json() {
+        let input = Cursor::new(r#"{"name": "nu", "ag"#);
+
+        let error = Value::from_json_reader(input, Tag::unknown())
+            .expect_err("truncated JSON should not parse")
+            .into_diagnostic();
+
+        let label = error.labels[0].message.clone().unwrap_or_default();
+        assert!(label.contains("at byte 18"), "unexpected label: {}", label);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::value::{merge_descriptors, UntaggedValue, Value};
+use nu_errors::ShellError;
+
+/// Render a table as a GitHub-flavored Markdown table, with a header row of the table's columns. Cells are
+/// converted with `as_lossy_string` and have any `|` escaped. When `pretty` is set, columns are padded so that
+/// they line up.
+pub fn table_to_markdown(table: &[Value], pretty: bool) -> Result<String, ShellError> {
+    let headers = merge_descriptors(table);
+
+    let mut rows: Vec<Vec<String>> = Vec::with_capacity(table.len() + 1);
+    rows.push(headers.iter().map(|header| escape(header)).collect());
+
+    for row in table {
+        let cells = headers
+            .iter()
+            .map(|header| cell(row, header))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.push(cells);
+    }
+
+    let widths: Vec<usize> = if pretty {
+        (0..headers.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect()
+    } else {
+        vec![0; headers.len()]
+    };
+
+    let mut output = String::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        output.push_str(&render_line(row, &widths, pretty));
+
+        if index == 0 {
+            let separators: Vec<String> = widths
+                .iter()
+                .map(|width| "-".repeat((*width).max(1)))
+                .collect();
+            output.push_str(&render_line(&separators, &widths, pretty));
+        }
+    }
+
+    Ok(output)
+}
+
+fn cell(row: &Value, header: &str) -> Result<String, ShellError> {
+    let value = match &row.value {
+        UntaggedValue::Row(dict) => match dict.entries.get(header) {
+            Some(value) => value,
+            None => return Ok(String::new()),
+        },
+        _ if header == "<value>" => row,
+        _ => return Ok(String::new()),
+    };
+
+    match &value.value {
+        UntaggedValue::Error(err) => Err(err.clone()),
+        _ => Ok(escape(&value.as_lossy_string())),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn render_line(cells: &[String], widths: &[usize], pretty: bool) -> String {
+    if pretty {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+
+        format!("| {} |\n", padded.join(" | "))
+    } else {
+        format!("|{}|\n", cells.join("|"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::table_to_markdown;
+    use crate::test_support::{int, row, string};
+    use crate::Value;
+    use nu_errors::ShellError;
+
+    fn shells() -> Vec<Value> {
+        vec![
+            row(vec![("name", string("nu")), ("age", int(1))]),
+            row(vec![("name", string("bash")), ("age", int(30))]),
+        ]
+    }
+
+    #[test]
+    fn renders_a_basic_table() -> Result<(), ShellError> {
+        assert_eq!(
+            table_to_markdown(&shells(), false)?,
+            "|name|age|\n|-|-|\n|nu|1|\n|bash|30|\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn renders_an_aligned_table() -> Result<(), ShellError> {
+        assert_eq!(
+            table_to_markdown(&shells(), true)?,
+            "| name | age |\n| ---- | --- |\n| nu   | 1   |\n| bash | 30  |\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_pipes_in_cells() -> Result<(), ShellError> {
+        let table = vec![row(vec![("command", string("ls | sort-by size"))])];
+
+        assert_eq!(
+            table_to_markdown(&table, false)?,
+            "|command|\n|-|\n|ls \\| sort-by size|\n"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::{UntaggedValue, Value};
+use nu_errors::ShellError;
+use nu_source::Tag;
+
+impl Value {
+    /// Parse space-separated text, such as the output of `ls -l`, into a table of strings. Blank lines are
+    /// skipped. Without `headers` the columns are named `Column1`, `Column2` and so on.
+    ///
+    /// By default any run of whitespace separates two values. In `aligned` mode each column instead starts
+    /// where its header (or, without headers, the first line's value) starts, so values may contain spaces.
+    /// Without headers the first line is still a row of data, so its own values can't contain spaces.
+    pub fn from_ssv(
+        input: &str,
+        headers: bool,
+        aligned: bool,
+        tag: impl Into<Tag>,
+    ) -> Result<Value, ShellError> {
+        let tag = tag.into();
+        let mut lines = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .peekable();
+
+        let first = match lines.peek() {
+            Some(first) => words(*first),
+            None => return Ok(UntaggedValue::Table(vec![]).into_value(&tag)),
+        };
+
+        let columns: Vec<(String, usize)> = if headers {
+            lines.next();
+            first
+                .into_iter()
+                .map(|(start, word)| (word.to_string(), start))
+                .collect()
+        } else {
+            first
+                .into_iter()
+                .enumerate()
+                .map(|(index, (start, _))| (format!("Column{}", index + 1), start))
+                .collect()
+        };
+
+        let mut rows = vec![];
+
+        for line in lines {
+            let values = if aligned {
+                split_aligned(line, &columns)
+            } else {
+                let values: Vec<String> = line.split_whitespace().map(String::from).collect();
+
+                if values.len() > columns.len() {
+                    return Err(ShellError::labeled_error(
+                        "Too many values in row",
+                        format!(
+                            "found {} values for {} columns",
+                            values.len(),
+                            columns.len()
+                        ),
+                        &tag,
+                    ));
+                }
+
+                values
+            };
+
+            let mut row = TaggedDictBuilder::new(&tag);
+            let mut values = values.into_iter();
+
+            for (name, _) in columns.iter() {
+                match values.next() {
+                    Some(value) => row.insert_untagged(name.clone(), UntaggedValue::string(value)),
+                    None => row.insert_untagged(name.clone(), UntaggedValue::nothing()),
+                }
+            }
+
+            rows.push(row.into_value());
+        }
+
+        Ok(UntaggedValue::Table(rows).into_value(&tag))
+    }
+}
+
+/// Find each whitespace-separated word in a line along with the character position it starts at
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut words = vec![];
+    let mut start = None;
+
+    for (position, (index, c)) in line.char_indices().enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((position, index)),
+            (true, Some((word_position, word_index))) => {
+                words.push((word_position, &line[word_index..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((word_position, word_index)) = start {
+        words.push((word_position, &line[word_index..]));
+    }
+
+    words
+}
+
+/// Split a line into the text under each column, where every column runs from its own start position up to
+/// the start of the next column
+fn split_aligned(line: &str, columns: &[(String, usize)]) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, (_, start))| {
+            let end = columns
+                .get(index + 1)
+                .map(|(_, next)| *next)
+                .unwrap_or_else(|| chars.len())
+                .min(chars.len());
+            let start = (*start).min(end);
+
+            chars[start..end]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{row, string};
+    use crate::{UntaggedValue, Value};
+    use nu_errors::ShellError;
+    use nu_source::Tag;
+
+    #[test]
+    fn from_ssv_with_minimal_whitespace() -> Result<(), ShellError> {
+        let input = "name  size\nnu 10\n\nbash    30\n";
+
+        let table = Value::from_ssv(input, true, false, Tag::unknown())?;
+
+        assert!(table.equals_ignoring_tags(
+            &UntaggedValue::table(&[
+                row(vec![("name", string("nu")), ("size", string("10"))]),
+                row(vec![("name", string("bash")), ("size", string("30"))]),
+            ])
+            .into_untagged_value()
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_ssv_in_aligned_mode() -> Result<(), ShellError> {
+        let input = "\
+permissions  modified      name
+-rw-r--r--   Mar 3 12:00   Cargo.toml
+drwxr-xr-x   Apr 10 09:30  src
+";
+
+        let table = Value::from_ssv(input, true, true, Tag::unknown())?;
+
+        assert!(table.equals_ignoring_tags(
+            &UntaggedValue::table(&[
+                row(vec![
+                    ("permissions", string("-rw-r--r--")),
+                    ("modified", string("Mar 3 12:00")),
+                    ("name", string("Cargo.toml")),
+                ]),
+                row(vec![
+                    ("permissions", string("drwxr-xr-x")),
+                    ("modified", string("Apr 10 09:30")),
+                    ("name", string("src")),
+                ]),
+            ])
+            .into_untagged_value()
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_ssv_without_headers() -> Result<(), ShellError> {
+        let table = Value::from_ssv("a b\nc d", false, false, Tag::unknown())?;
+
+        assert!(table.equals_ignoring_tags(
+            &UntaggedValue::table(&[
+                row(vec![("Column1", string("a")), ("Column2", string("b"))]),
+                row(vec![("Column1", string("c")), ("Column2", string("d"))]),
+            ])
+            .into_untagged_value()
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_ssv_in_aligned_mode_without_headers() -> Result<(), ShellError> {
+        let input = "\
+-rw-r--r--   yesterday     Cargo.toml
+drwxr-xr-x   Apr 10 09:30  src
+";
+
+        let table = Value::from_ssv(input, false, true, Tag::unknown())?;
+
+        assert!(table.equals_ignoring_tags(
+            &UntaggedValue::table(&[
+                row(vec![
+                    ("Column1", string("-rw-r--r--")),
+                    ("Column2", string("yesterday")),
+                    ("Column3", string("Cargo.toml")),
+                ]),
+                row(vec![
+                    ("Column1", string("drwxr-xr-x")),
+                    ("Column2", string("Apr 10 09:30")),
+                    ("Column3", string("src")),
+                ]),
+            ])
+            .into_untagged_value()
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_ssv_errors_on_extra_values() {
+        let error = Value::from_ssv("name\nnu shell", true, false, Tag::unknown())
+            .expect_err("a row with more values than columns should not parse")
+            .into_diagnostic();
+
+        assert_eq!(
+            error.labels[0].message.clone().unwrap_or_default(),
+            "found 2 values for 1 columns"
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::table::column_of;
+use crate::value::{UntaggedValue, Value};
+use nu_errors::ShellError;
+use nu_source::Tag;
+
+/// Parse an XML document into nested rows. Each element becomes a row with its `tag` name, a row of its
+/// `attributes` and a table of its `children`. Text becomes a string, and text that is only whitespace,
+/// comments and processing instructions are left out. Element and attribute names are kept without their
+/// namespace prefix, so `<nu:shell>` becomes a row with the tag `shell`.
+pub fn from_xml(input: &str, tag: impl Into<Tag>) -> Result<Value, ShellError> {
+    let tag = tag.into();
+
+    let document = roxmltree::Document::parse(input).map_err(|err| {
+        ShellError::labeled_error("Could not parse as XML", err.to_string(), &tag)
+    })?;
+
+    Ok(element_to_value(&document.root_element(), &tag))
+}
+
+fn element_to_value(node: &roxmltree::Node, tag: &Tag) -> Value {
+    let mut attributes = TaggedDictBuilder::new(tag);
+    for attribute in node.attributes() {
+        attributes.insert_untagged(attribute.name(), UntaggedValue::string(attribute.value()));
+    }
+
+    let children: Vec<Value> = node
+        .children()
+        .filter_map(|child| {
+            if child.is_element() {
+                Some(element_to_value(&child, tag))
+            } else if child.is_text() {
+                child
+                    .text()
+                    .filter(|text| !text.trim().is_empty())
+                    .map(|text| UntaggedValue::string(text).into_value(tag))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let mut element = TaggedDictBuilder::new(tag);
+    element.insert_untagged("tag", UntaggedValue::string(node.tag_name().name()));
+    element.insert_value("attributes", attributes.into_value());
+    element.insert_untagged("children", UntaggedValue::Table(children));
+    element.into_value()
+}
+
+/// Write the rows made by `from_xml` back out as an XML document. Attributes are written in the order of their
+/// columns. Any row missing its `tag` or `children`, or with a tag or attribute that isn't a valid XML name, is
+/// an error.
+pub fn to_xml(value: &Value) -> Result<String, ShellError> {
+    let mut output = String::new();
+    write_element(value, &mut output)?;
+
+    Ok(output)
+}
+
+fn write_element(value: &Value, output: &mut String) -> Result<(), ShellError> {
+    let tag = column_of(value, "tag")?;
+    let name = tag.as_forgiving_string()?;
+    expect_xml_name(name, &tag.tag)?;
+
+    let children = match &column_of(value, "children")?.value {
+        UntaggedValue::Table(children) => children,
+        _ => {
+            return Err(ShellError::labeled_error(
+                "Expected a table of children",
+                format!("the children of <{}> are not a table", name),
+                &value.tag,
+            ))
+        }
+    };
+
+    output.push('<');
+    output.push_str(name);
+
+    if let Ok(attributes) = column_of(value, "attributes") {
+        match &attributes.value {
+            UntaggedValue::Row(attributes) => {
+                for (key, attribute) in attributes.entries.iter() {
+                    expect_xml_name(key, &attribute.tag)?;
+                    output.push_str(&format!(
+                        " {}=\"{}\"",
+                        key,
+                        escape(&attribute.as_lossy_string())
+                    ));
+                }
+            }
+            _ => {
+                return Err(ShellError::type_error(
+                    "row",
+                    attributes.spanned_type_name(),
+                ))
+            }
+        }
+    }
+
+    if children.is_empty() {
+        output.push_str("/>");
+        return Ok(());
+    }
+
+    output.push('>');
+
+    for child in children {
+        match &child.value {
+            UntaggedValue::Row(_) => write_element(child, output)?,
+            _ => output.push_str(&escape(&child.as_lossy_string())),
+        }
+    }
+
+    output.push_str(&format!("</{}>", name));
+
+    Ok(())
+}
+
+/// Check that a name can be written as an XML element or attribute name
+fn expect_xml_name(name: &str, tag: &Tag) -> Result<(), ShellError> {
+    let mut chars = name.chars();
+
+    let valid = match chars.next() {
+        Some(first) if first.is_alphabetic() || first == '_' || first == ':' => {
+            chars.all(|c| c.is_alphanumeric() || c == '_' || c == ':' || c == '-' || c == '.')
+        }
+        _ => false,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ShellError::labeled_error(
+            "Invalid XML name",
+            format!("'{}' is not a valid element or attribute name", name),
+            tag,
+        ))
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_xml, to_xml};
+    use crate::test_support::{row, string};
+    use crate::{UntaggedValue, Value};
+    use nu_errors::ShellError;
+    use nu_source::Tag;
+
+    fn element(name: &str, attributes: Vec<(&str, Value)>, children: Vec<Value>) -> Value {
+        row(vec![
+            ("tag", string(name)),
+            ("attributes", row(attributes)),
+            (
+                "children",
+                UntaggedValue::table(&children).into_untagged_value(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn from_xml_maps_nested_elements() -> Result<(), ShellError> {
+        let input = "<shells>\n  <shell>nu</shell>\n  <shell>bash</shell>\n</shells>";
+
+        let value = from_xml(input, Tag::unknown())?;
+
+        assert!(value.equals_ignoring_tags(&element(
+            "shells",
+            vec![],
+            vec![
+                element("shell", vec![], vec![string("nu")]),
+                element("shell", vec![], vec![string("bash")]),
+            ]
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_xml_maps_attributes() -> Result<(), ShellError> {
+        let input = r#"<shell name="nu" version="0.11"><plugin enabled="true"/></shell>"#;
+
+        let value = from_xml(input, Tag::unknown())?;
+
+        assert!(value.equals_ignoring_tags(&element(
+            "shell",
+            vec![("name", string("nu")), ("version", string("0.11"))],
+            vec![element("plugin", vec![("enabled", string("true"))], vec![])]
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_xml_round_trips_from_xml() -> Result<(), ShellError> {
+        let input = r#"<shell name="nu" motto="a &amp; b"><plugin enabled="true"/><about>1 &lt; 2</about></shell>"#;
+
+        let value = from_xml(input, Tag::unknown())?;
+        let written = to_xml(&value)?;
+
+        assert_eq!(written, input);
+        assert!(from_xml(&written, Tag::unknown())?.equals_ignoring_tags(&value));
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_xml_errors_without_the_expected_structure() {
+        assert!(to_xml(&row(vec![("tag", string("shell"))])).is_err());
+        assert!(to_xml(&row(vec![("children", string("nu"))])).is_err());
+        assert!(to_xml(&string("nu")).is_err());
+    }
+
+    #[test]
+    fn to_xml_errors_on_invalid_names() {
+        assert!(to_xml(&element("a b", vec![], vec![])).is_err());
+        assert!(to_xml(&element("x><y", vec![], vec![])).is_err());
+        assert!(to_xml(&element("1shell", vec![], vec![])).is_err());
+        assert!(to_xml(&element("shell", vec![("a b", string("nu"))], vec![])).is_err());
+        assert!(to_xml(&element("shell", vec![("x=\"y\"", string("nu"))], vec![])).is_err());
+    }
+
+    #[test]
+    fn from_xml_drops_namespace_prefixes() -> Result<(), ShellError> {
+        let input = r#"<nu:shell xmlns:nu="https://www.nushell.sh"><nu:plugin/></nu:shell>"#;
+
+        let value = from_xml(input, Tag::unknown())?;
+
+        assert!(value.equals_ignoring_tags(&element(
+            "shell",
+            vec![],
+            vec![element("plugin", vec![], vec![])]
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_xml_errors_on_malformed_input() {
+        assert!(from_xml("<shell></nu>", Tag::unknown()).is_err());
+        assert!(from_xml("nu", Tag::unknown()).is_err());
+    }
+}
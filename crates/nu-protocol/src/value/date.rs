@@ -0,0 +1,306 @@
+use crate::type_name::{ShellTypeName, SpannedTypeName};
+use crate::value::primitive::Primitive;
+use crate::value::{UntaggedValue, Value};
+use chrono::{DateTime, TimeZone, Utc};
+use nu_errors::{ExpectedRange, ShellError};
+use nu_source::{SpannedItem, Tag};
+
+impl Value {
+    /// Describe a date relative to `now`, such as `3 days ago` or `in 2 hours`, using the largest unit that fits
+    pub fn humanize_date(&self, now: DateTime<Utc>) -> Result<String, ShellError> {
+        let date = match &self.value {
+            UntaggedValue::Primitive(Primitive::Date(date)) => date,
+            _ => return Err(ShellError::type_error("date", self.spanned_type_name())),
+        };
+
+        let seconds = now.signed_duration_since(*date).num_seconds();
+
+        let units = [
+            (365 * 86_400, "year"),
+            (30 * 86_400, "month"),
+            (7 * 86_400, "week"),
+            (86_400, "day"),
+            (3_600, "hour"),
+            (60, "minute"),
+            (1, "second"),
+        ];
+
+        let (amount, unit) = match units.iter().find(|(size, _)| seconds.abs() >= *size) {
+            Some((size, unit)) => (seconds.abs() / size, unit),
+            None => return Ok("now".to_string()),
+        };
+
+        let unit = if amount == 1 {
+            (*unit).to_string()
+        } else {
+            format!("{}s", unit)
+        };
+
+        if seconds > 0 {
+            Ok(format!("{} {} ago", amount, unit))
+        } else {
+            Ok(format!("in {} {}", amount, unit))
+        }
+    }
+
+    /// Get the number of seconds between the Unix epoch and a date
+    pub fn to_timestamp(&self) -> Result<i64, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Date(date)) => Ok(date.timestamp()),
+            _ => Err(ShellError::type_error("date", self.spanned_type_name())),
+        }
+    }
+
+    /// Create a date from a number of seconds since the Unix epoch
+    pub fn from_timestamp(secs: i64, tag: impl Into<Tag>) -> Result<Value, ShellError> {
+        let tag = tag.into();
+
+        match Utc.timestamp_opt(secs, 0).single() {
+            Some(date) => Ok(UntaggedValue::date(date).into_value(tag)),
+            None => Err(ShellError::range_error(
+                ExpectedRange::I64,
+                &secs.spanned(tag.span),
+                "converting a timestamp into a date",
+            )),
+        }
+    }
+
+    /// Add a duration to a date, giving a later date tagged like the date. Errors point at both operands.
+    pub fn add_duration(&self, duration: &Value) -> Result<Value, ShellError> {
+        self.shift_by(duration, false)
+    }
+
+    /// Subtract a duration from a date, giving an earlier date tagged like the date
+    pub fn sub_duration(&self, duration: &Value) -> Result<Value, ShellError> {
+        self.shift_by(duration, true)
+    }
+
+    fn shift_by(&self, duration: &Value, backward: bool) -> Result<Value, ShellError> {
+        match (&self.value, &duration.value) {
+            (
+                UntaggedValue::Primitive(Primitive::Date(date)),
+                UntaggedValue::Primitive(Primitive::Duration(secs)),
+            ) => Ok(
+                UntaggedValue::date(shift_date(date, *secs, &duration.tag, backward)?)
+                    .into_value(&self.tag),
+            ),
+            _ => Err(ShellError::coerce_error(
+                self.spanned_type_name(),
+                duration.spanned_type_name(),
+            )),
+        }
+    }
+}
+
+impl UntaggedValue {
+    /// Add two values. A date plus a duration, in either order, gives a later date.
+    pub fn add(&self, other: &UntaggedValue) -> Result<UntaggedValue, ShellError> {
+        match (self, other) {
+            (
+                UntaggedValue::Primitive(Primitive::Date(date)),
+                UntaggedValue::Primitive(Primitive::Duration(secs)),
+            )
+            | (
+                UntaggedValue::Primitive(Primitive::Duration(secs)),
+                UntaggedValue::Primitive(Primitive::Date(date)),
+            ) => Ok(UntaggedValue::date(shift_date(
+                date,
+                *secs,
+                &Tag::unknown(),
+                false,
+            )?)),
+            _ => Err(ShellError::coerce_error(
+                self.type_name().spanned_unknown(),
+                other.type_name().spanned_unknown(),
+            )),
+        }
+    }
+
+    /// Subtract one value from another. A date minus a duration gives an earlier date.
+    pub fn sub(&self, other: &UntaggedValue) -> Result<UntaggedValue, ShellError> {
+        match (self, other) {
+            (
+                UntaggedValue::Primitive(Primitive::Date(date)),
+                UntaggedValue::Primitive(Primitive::Duration(secs)),
+            ) => Ok(UntaggedValue::date(shift_date(
+                date,
+                *secs,
+                &Tag::unknown(),
+                true,
+            )?)),
+            _ => Err(ShellError::coerce_error(
+                self.type_name().spanned_unknown(),
+                other.type_name().spanned_unknown(),
+            )),
+        }
+    }
+}
+
+/// Move a date forward, or backward if `backward` is set, by a number of seconds, erroring if the result
+/// can't be represented
+fn shift_date(
+    date: &DateTime<Utc>,
+    secs: u64,
+    tag: &Tag,
+    backward: bool,
+) -> Result<DateTime<Utc>, ShellError> {
+    let shifted = chrono::Duration::from_std(std::time::Duration::from_secs(secs))
+        .ok()
+        .and_then(|duration| {
+            if backward {
+                date.checked_sub_signed(duration)
+            } else {
+                date.checked_add_signed(duration)
+            }
+        });
+
+    shifted.ok_or_else(|| {
+        ShellError::range_error(
+            ExpectedRange::I64,
+            &secs.spanned(tag.span),
+            if backward {
+                "subtracting a duration from a date"
+            } else {
+                "adding a duration to a date"
+            },
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{UntaggedValue, Value};
+    use chrono::{DateTime, TimeZone, Utc};
+    use nu_errors::ShellError;
+    use nu_source::{Span, Tag};
+
+    fn now() -> DateTime<Utc> {
+        Utc.ymd(2020, 3, 10).and_hms(12, 0, 0)
+    }
+
+    fn date(date: DateTime<Utc>) -> Value {
+        UntaggedValue::date(date).into_untagged_value()
+    }
+
+    #[test]
+    fn humanize_a_past_date() -> Result<(), ShellError> {
+        assert_eq!(
+            date(Utc.ymd(2020, 3, 7).and_hms(12, 0, 0)).humanize_date(now())?,
+            "3 days ago"
+        );
+        assert_eq!(
+            date(Utc.ymd(2020, 3, 10).and_hms(11, 59, 0)).humanize_date(now())?,
+            "1 minute ago"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn humanize_a_future_date() -> Result<(), ShellError> {
+        assert_eq!(
+            date(Utc.ymd(2020, 3, 10).and_hms(14, 0, 0)).humanize_date(now())?,
+            "in 2 hours"
+        );
+        assert_eq!(
+            date(Utc.ymd(2021, 4, 1).and_hms(12, 0, 0)).humanize_date(now())?,
+            "in 1 year"
+        );
+
+        Ok(())
+    }
+
+    fn duration(secs: u64, start: usize) -> Value {
+        UntaggedValue::duration(secs).into_value(Tag::from(Span::new(start, start + 2)))
+    }
+
+    #[test]
+    fn add_a_day_to_a_date() -> Result<(), ShellError> {
+        let date = UntaggedValue::date(Utc.ymd(2020, 2, 28).and_hms(12, 0, 0));
+        let day = UntaggedValue::duration(86_400);
+
+        assert_eq!(
+            date.add(&day)?,
+            UntaggedValue::date(Utc.ymd(2020, 2, 29).and_hms(12, 0, 0))
+        );
+        assert_eq!(day.add(&date)?, date.add(&day)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn subtract_across_a_month_boundary() -> Result<(), ShellError> {
+        let date = UntaggedValue::date(Utc.ymd(2020, 3, 1).and_hms(6, 0, 0));
+
+        assert_eq!(
+            date.sub(&UntaggedValue::duration(2 * 86_400))?,
+            UntaggedValue::date(Utc.ymd(2020, 2, 28).and_hms(6, 0, 0))
+        );
+        assert!(UntaggedValue::duration(60).sub(&date).is_err());
+        assert!(date
+            .add(&UntaggedValue::duration(u64::max_value()))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn date_arithmetic_keeps_the_date_tag() -> Result<(), ShellError> {
+        let tag = Tag::from(Span::new(0, 10));
+        let value = UntaggedValue::date(Utc.ymd(2020, 3, 1).and_hms(6, 0, 0)).into_value(&tag);
+
+        assert_eq!(value.add_duration(&duration(60, 13))?.tag, tag);
+        assert_eq!(value.sub_duration(&duration(60, 13))?.tag, tag);
+        assert_eq!(
+            value.add_duration(&duration(60, 13))?.value,
+            UntaggedValue::date(Utc.ymd(2020, 3, 1).and_hms(6, 1, 0))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn date_arithmetic_errors_point_at_the_operands() {
+        let value = UntaggedValue::date(Utc.ymd(2020, 3, 1).and_hms(6, 0, 0))
+            .into_value(Tag::from(Span::new(0, 10)));
+        let int = UntaggedValue::int(1).into_value(Tag::from(Span::new(13, 14)));
+
+        assert!(value.add_duration(&duration(u64::max_value(), 13)).is_err());
+        assert!(duration(1, 0).sub_duration(&value).is_err());
+        assert!(duration(1, 0).add_duration(&value).is_err());
+
+        let error = value
+            .add_duration(&int)
+            .expect_err("a date and an integer can't be added")
+            .into_diagnostic();
+        let spans: Vec<Span> = error.labels.iter().map(|label| label.span).collect();
+        assert!(spans.contains(&Span::new(0, 10)));
+        assert!(spans.contains(&Span::new(13, 14)));
+    }
+
+    #[test]
+    fn timestamps_round_trip() -> Result<(), ShellError> {
+        let value = date(Utc.ymd(2020, 3, 10).and_hms(12, 0, 0));
+
+        assert_eq!(value.to_timestamp()?, 1_583_841_600);
+        assert_eq!(Value::from_timestamp(1_583_841_600, Tag::unknown())?, value);
+        assert_eq!(Value::from_timestamp(0, Tag::unknown())?.to_timestamp()?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_timestamp_errors_on_non_dates() {
+        assert!(UntaggedValue::int(1_583_841_600)
+            .into_untagged_value()
+            .to_timestamp()
+            .is_err());
+    }
+
+    #[test]
+    fn humanize_the_same_moment() -> Result<(), ShellError> {
+        assert_eq!(date(now()).humanize_date(now())?, "now");
+
+        Ok(())
+    }
+}
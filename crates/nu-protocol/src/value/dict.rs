@@ -172,6 +172,15 @@ impl Dictionary {
     pub fn insert_data_at_key(&mut self, name: &str, value: Value) {
         self.entries.insert(name.to_string(), value);
     }
+
+    /// Release any capacity the dictionary holds beyond its current entries. The values themselves are not
+    /// touched, see `Value::shrink_to_fit` for that.
+    pub fn shrink_to_fit(&mut self) {
+        if self.entries.capacity() > self.entries.len() {
+            let entries = std::mem::replace(&mut self.entries, IndexMap::new());
+            self.entries = entries.into_iter().collect();
+        }
+    }
 }
 
 /// A helper to help create dictionaries for you. It has the ability to insert values into the dictionary while maintaining the tags that need to be applied to the individual members
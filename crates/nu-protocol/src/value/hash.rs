@@ -0,0 +1,137 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::primitive::Primitive;
+use crate::value::{UntaggedValue, Value};
+use nu_errors::ShellError;
+use sha2::{Digest, Sha256};
+
+impl Value {
+    /// Hash the UTF-8 bytes of a string or the contents of a binary value with SHA-256, giving the digest as
+    /// lowercase hex
+    pub fn sha256_hex(&self) -> Result<String, ShellError> {
+        Ok(format!("{:x}", Sha256::digest(self.hash_input()?)))
+    }
+
+    /// Hash the UTF-8 bytes of a string or the contents of a binary value with MD5, giving the digest as
+    /// lowercase hex
+    pub fn md5_hex(&self) -> Result<String, ShellError> {
+        Ok(format!("{:x}", md5::compute(self.hash_input()?)))
+    }
+
+    /// Compute the CRC-32 checksum of the UTF-8 bytes of a string or the contents of a binary value
+    pub fn crc32(&self) -> Result<u32, ShellError> {
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(self.hash_input()?);
+
+        Ok(hasher.finalize())
+    }
+
+    /// Get the bytes to hash, which are the UTF-8 bytes of a string or line, or the contents of a binary value
+    fn hash_input(&self) -> Result<&[u8], ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::Binary(bytes)) => Ok(&bytes[..]),
+            UntaggedValue::Primitive(primitive) => match primitive.text() {
+                Some(text) => Ok(text.as_bytes()),
+                None => Err(ShellError::type_error(
+                    "string or binary",
+                    self.spanned_type_name(),
+                )),
+            },
+            _ => Err(ShellError::type_error(
+                "string or binary",
+                self.spanned_type_name(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UntaggedValue;
+    use nu_errors::ShellError;
+
+    #[test]
+    fn sha256_of_a_string() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::string("abc")
+                .into_untagged_value()
+                .sha256_hex()?,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sha256_of_binary_matches_the_same_bytes_as_a_string() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::binary(b"abc".to_vec())
+                .into_untagged_value()
+                .sha256_hex()?,
+            UntaggedValue::string("abc")
+                .into_untagged_value()
+                .sha256_hex()?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sha256_of_a_line_matches_the_same_string() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::line("abc")
+                .into_untagged_value()
+                .sha256_hex()?,
+            UntaggedValue::string("abc")
+                .into_untagged_value()
+                .sha256_hex()?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn md5_of_a_string() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::string("abc")
+                .into_untagged_value()
+                .md5_hex()?,
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn crc32_of_a_string_and_binary() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::string("123456789")
+                .into_untagged_value()
+                .crc32()?,
+            0xcbf4_3926
+        );
+        assert_eq!(
+            UntaggedValue::binary(b"123456789".to_vec())
+                .into_untagged_value()
+                .crc32()?,
+            0xcbf4_3926
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn md5_and_crc32_error_on_non_text_input() {
+        let value = UntaggedValue::boolean(true).into_untagged_value();
+
+        assert!(value.md5_hex().is_err());
+        assert!(value.crc32().is_err());
+    }
+
+    #[test]
+    fn sha256_errors_on_other_types() {
+        assert!(UntaggedValue::int(1)
+            .into_untagged_value()
+            .sha256_hex()
+            .is_err());
+    }
+}
@@ -0,0 +1,531 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::primitive::Primitive;
+use crate::value::range::RangeInclusion;
+use crate::value::table::{column_of, compare_values, sort_values};
+use crate::value::{UntaggedValue, Value};
+use bigdecimal::BigDecimal;
+use nu_errors::ShellError;
+use nu_source::{SpannedItem, Tag};
+use num_bigint::BigInt;
+use num_traits::cast::{FromPrimitive, ToPrimitive};
+use num_traits::Zero;
+use std::cmp::Ordering;
+
+impl UntaggedValue {
+    /// Bucket the rows of a table into `bins` equal-width ranges of the numeric values of a column, giving a
+    /// table of `range`/`count` rows. The last range includes the maximum value.
+    pub fn histogram(
+        table: &[Value],
+        column: &str,
+        bins: usize,
+    ) -> Result<UntaggedValue, ShellError> {
+        if bins == 0 {
+            return Err(ShellError::untagged_runtime_error(
+                "histogram needs at least one bin",
+            ));
+        }
+
+        let values = table
+            .iter()
+            .map(|row| as_decimal(column_of(row, column)?))
+            .collect::<Result<Vec<BigDecimal>, ShellError>>()?;
+
+        let (min, max) = match (values.iter().min(), values.iter().max()) {
+            (Some(min), Some(max)) => (min.clone(), max.clone()),
+            _ => return Ok(UntaggedValue::Table(vec![])),
+        };
+
+        let spread = max.clone() - min.clone();
+        let bin_count = BigDecimal::new(BigInt::from(bins), 0);
+        let width = spread.clone() / bin_count.clone();
+
+        let mut counts = vec![0usize; bins];
+
+        for value in values {
+            let bin = if spread.is_zero() {
+                0
+            } else {
+                let (bin, _) = ((value - min.clone()) * bin_count.clone() / spread.clone())
+                    .with_scale(0)
+                    .as_bigint_and_exponent();
+                bin.to_usize().unwrap_or(bins - 1).min(bins - 1)
+            };
+
+            counts[bin] += 1;
+        }
+
+        let rows = counts
+            .into_iter()
+            .enumerate()
+            .map(|(idx, count)| {
+                let from = min.clone() + width.clone() * BigDecimal::new(BigInt::from(idx), 0);
+                let (to, to_inclusion) = if idx == bins - 1 {
+                    (max.clone(), RangeInclusion::Inclusive)
+                } else {
+                    (
+                        min.clone() + width.clone() * BigDecimal::new(BigInt::from(idx + 1), 0),
+                        RangeInclusion::Exclusive,
+                    )
+                };
+
+                let mut row = TaggedDictBuilder::new(Tag::unknown());
+                row.insert_untagged(
+                    "range",
+                    UntaggedValue::range(
+                        (
+                            Primitive::Decimal(from).spanned_unknown(),
+                            RangeInclusion::Inclusive,
+                        ),
+                        (Primitive::Decimal(to).spanned_unknown(), to_inclusion),
+                    ),
+                );
+                row.insert_untagged("count", UntaggedValue::int(count));
+                row.into_value()
+            })
+            .collect();
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Find the row of a table with the smallest value in the given column
+    pub fn min_by(table: &[Value], column: &str) -> Result<Value, ShellError> {
+        extreme_by(table, column, Ordering::Less)
+    }
+
+    /// Find the row of a table with the largest value in the given column
+    pub fn max_by(table: &[Value], column: &str) -> Result<Value, ShellError> {
+        extreme_by(table, column, Ordering::Greater)
+    }
+
+    /// Find the median of the values. For an even number of values, the two middle values are averaged.
+    pub fn median(values: &[Value]) -> Result<UntaggedValue, ShellError> {
+        for value in values {
+            as_decimal(value)?;
+        }
+
+        let sorted = sort_values(values)?;
+        let middle = sorted.len() / 2;
+
+        match sorted.len() {
+            0 => Err(ShellError::untagged_runtime_error(
+                "Cannot find the median of an empty list",
+            )),
+            len if len % 2 == 1 => Ok(sorted[middle].value.clone()),
+            _ => {
+                let sum = as_decimal(sorted[middle - 1])? + as_decimal(sorted[middle])?;
+                Ok(UntaggedValue::decimal(
+                    sum / BigDecimal::new(BigInt::from(2), 0),
+                ))
+            }
+        }
+    }
+
+    /// Find the most frequent value(s), returning a table of every value that occurs most often
+    pub fn mode(values: &[Value]) -> Result<UntaggedValue, ShellError> {
+        let sorted = sort_values(values)?;
+
+        if sorted.is_empty() {
+            return Err(ShellError::untagged_runtime_error(
+                "Cannot find the mode of an empty list",
+            ));
+        }
+
+        let mut runs: Vec<(&Value, usize)> = vec![];
+
+        for value in sorted {
+            match runs.last_mut() {
+                Some((last, count)) if compare_values(last, value)? == Ordering::Equal => {
+                    *count += 1
+                }
+                _ => runs.push((value, 1)),
+            }
+        }
+
+        let most = runs.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+        Ok(UntaggedValue::Table(
+            runs.into_iter()
+                .filter(|(_, count)| *count == most)
+                .map(|(value, _)| value.clone())
+                .collect(),
+        ))
+    }
+
+    /// Find the variance of numeric values. If `sample` is set, the sample variance (dividing by one less than
+    /// the number of values) is computed instead of the population variance.
+    pub fn variance(values: &[Value], sample: bool) -> Result<UntaggedValue, ShellError> {
+        Ok(UntaggedValue::decimal(variance_of(values, sample)?))
+    }
+
+    /// Find the standard deviation of numeric values. If `sample` is set, the sample standard deviation is computed
+    /// instead of the population standard deviation.
+    pub fn stddev(values: &[Value], sample: bool) -> Result<UntaggedValue, ShellError> {
+        let variance = variance_of(values, sample)?;
+
+        match sqrt(&variance) {
+            Some(stddev) => Ok(UntaggedValue::decimal(stddev)),
+            None => Err(ShellError::untagged_runtime_error(
+                "Could not compute the square root of the variance",
+            )),
+        }
+    }
+
+    /// Multiply numeric values together. An empty list gives 1, and the product is promoted to a decimal if any
+    /// of the values are decimals.
+    pub fn product(values: &[Value]) -> Result<UntaggedValue, ShellError> {
+        let mut product = UntaggedValue::int(1);
+
+        for value in values {
+            product = match (&product, &value.value) {
+                (
+                    UntaggedValue::Primitive(Primitive::Int(left)),
+                    UntaggedValue::Primitive(Primitive::Int(right)),
+                ) => UntaggedValue::int(left * right),
+                (UntaggedValue::Primitive(Primitive::Int(left)), _) => {
+                    UntaggedValue::decimal(BigDecimal::new(left.clone(), 0) * as_decimal(value)?)
+                }
+                (UntaggedValue::Primitive(Primitive::Decimal(left)), _) => {
+                    UntaggedValue::decimal(left * as_decimal(value)?)
+                }
+                _ => product,
+            };
+        }
+
+        Ok(product)
+    }
+
+    /// Compute the running totals of numeric values, giving a table where each element is the sum of the values up
+    /// to and including that point
+    pub fn cumulative_sum(values: &[Value]) -> Result<UntaggedValue, ShellError> {
+        let mut sum = UntaggedValue::int(0);
+        let mut sums = Vec::with_capacity(values.len());
+
+        for value in values {
+            sum = match (&sum, &value.value) {
+                (
+                    UntaggedValue::Primitive(Primitive::Int(left)),
+                    UntaggedValue::Primitive(Primitive::Int(right)),
+                ) => UntaggedValue::int(left + right),
+                (UntaggedValue::Primitive(Primitive::Int(left)), _) => {
+                    UntaggedValue::decimal(BigDecimal::new(left.clone(), 0) + as_decimal(value)?)
+                }
+                (UntaggedValue::Primitive(Primitive::Decimal(left)), _) => {
+                    UntaggedValue::decimal(left + as_decimal(value)?)
+                }
+                _ => sum,
+            };
+
+            sums.push(sum.clone().into_value(&value.tag));
+        }
+
+        Ok(UntaggedValue::Table(sums))
+    }
+}
+
+fn variance_of(values: &[Value], sample: bool) -> Result<BigDecimal, ShellError> {
+    let numbers = values
+        .iter()
+        .map(as_decimal)
+        .collect::<Result<Vec<BigDecimal>, ShellError>>()?;
+
+    let denominator = match (numbers.len(), sample) {
+        (0, _) => {
+            return Err(ShellError::untagged_runtime_error(
+                "Cannot find the variance of an empty list",
+            ))
+        }
+        (1, true) => {
+            return Err(ShellError::untagged_runtime_error(
+                "Cannot find the sample variance of a single value",
+            ))
+        }
+        (len, true) => len - 1,
+        (len, false) => len,
+    };
+
+    let count = BigDecimal::new(BigInt::from(numbers.len()), 0);
+    let mean = numbers
+        .iter()
+        .fold(BigDecimal::zero(), |sum, number| sum + number.clone())
+        / count;
+
+    let squares = numbers.into_iter().fold(BigDecimal::zero(), |sum, number| {
+        let deviation = number - mean.clone();
+        sum + deviation.clone() * deviation
+    });
+
+    Ok(squares / BigDecimal::new(BigInt::from(denominator), 0))
+}
+
+const SQRT_ITERATIONS: usize = 5;
+
+/// Square root using a floating point estimate refined with Newton's method
+fn sqrt(value: &BigDecimal) -> Option<BigDecimal> {
+    if value.is_zero() {
+        return Some(BigDecimal::zero());
+    }
+
+    let two = BigDecimal::new(BigInt::from(2), 0);
+    let mut guess = BigDecimal::from_f64(value.to_f64()?.sqrt())?;
+
+    for _ in 0..SQRT_ITERATIONS {
+        if guess.is_zero() {
+            return None;
+        }
+
+        guess = (value.clone() / guess.clone() + guess) / two.clone();
+    }
+
+    Some(guess)
+}
+
+fn extreme_by(table: &[Value], column: &str, wanted: Ordering) -> Result<Value, ShellError> {
+    let mut rows = table.iter();
+
+    let mut best = rows.next().ok_or_else(|| {
+        ShellError::untagged_runtime_error("Cannot find the minimum or maximum of an empty table")
+    })?;
+    let mut best_value = column_of(best, column)?;
+
+    for row in rows {
+        let value = column_of(row, column)?;
+
+        if compare_values(value, best_value)? == wanted {
+            best = row;
+            best_value = value;
+        }
+    }
+
+    Ok(best.clone())
+}
+
+/// Get a numeric value as a decimal, erroring if the value isn't a number
+pub(crate) fn as_decimal(value: &Value) -> Result<BigDecimal, ShellError> {
+    match &value.value {
+        UntaggedValue::Primitive(Primitive::Int(int)) => Ok(BigDecimal::new(int.clone(), 0)),
+        UntaggedValue::Primitive(Primitive::Decimal(decimal)) => Ok(decimal.clone()),
+        UntaggedValue::Primitive(Primitive::Bytes(bytes)) => {
+            Ok(BigDecimal::new(BigInt::from(*bytes), 0))
+        }
+        _ => Err(ShellError::type_error("number", value.spanned_type_name())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{int, row, string};
+    use crate::{Primitive, UntaggedValue, Value};
+    use bigdecimal::BigDecimal;
+    use nu_errors::ShellError;
+    use std::str::FromStr;
+
+    fn counts(histogram: UntaggedValue) -> Vec<Value> {
+        match histogram {
+            UntaggedValue::Table(rows) => rows
+                .iter()
+                .map(|row| match &row.value {
+                    UntaggedValue::Row(dict) => dict.get_data("count").borrow().clone(),
+                    _ => unreachable!("histogram rows are rows"),
+                })
+                .collect(),
+            _ => unreachable!("histogram is a table"),
+        }
+    }
+
+    #[test]
+    fn histogram_buckets_a_numeric_column() -> Result<(), ShellError> {
+        let table: Vec<Value> = (1..=10).map(|n| row(vec![("size", int(n))])).collect();
+
+        assert_eq!(
+            counts(UntaggedValue::histogram(&table, "size", 3)?),
+            vec![int(3), int(3), int(4)]
+        );
+        assert_eq!(
+            counts(UntaggedValue::histogram(&table, "size", 2)?),
+            vec![int(5), int(5)]
+        );
+
+        Ok(())
+    }
+
+    fn people() -> Vec<Value> {
+        vec![
+            row(vec![("name", string("andres")), ("age", int(32))]),
+            row(vec![("name", string("jonathan")), ("age", int(28))]),
+            row(vec![("name", string("yehuda")), ("age", int(40))]),
+        ]
+    }
+
+    #[test]
+    fn min_by_returns_the_row_with_the_smallest_value() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::min_by(&people(), "age")?,
+            row(vec![("name", string("jonathan")), ("age", int(28))])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_by_returns_the_row_with_the_largest_value() -> Result<(), ShellError> {
+        assert_eq!(
+            UntaggedValue::max_by(&people(), "age")?,
+            row(vec![("name", string("yehuda")), ("age", int(40))])
+        );
+        assert_eq!(
+            UntaggedValue::max_by(&people(), "name")?,
+            row(vec![("name", string("yehuda")), ("age", int(40))])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn min_by_errors_on_an_empty_table_or_missing_column() {
+        assert!(UntaggedValue::min_by(&[], "age").is_err());
+        assert!(UntaggedValue::max_by(&people(), "height").is_err());
+    }
+
+    fn decimal(input: &str) -> UntaggedValue {
+        UntaggedValue::decimal(BigDecimal::from_str(input).unwrap())
+    }
+
+    #[test]
+    fn median_of_an_odd_number_of_values() -> Result<(), ShellError> {
+        let values = vec![int(3), int(1), int(2)];
+
+        assert_eq!(UntaggedValue::median(&values)?, UntaggedValue::int(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn median_of_an_even_number_of_values() -> Result<(), ShellError> {
+        let values = vec![int(4), int(1), int(3), int(2)];
+
+        assert_eq!(UntaggedValue::median(&values)?, decimal("2.5"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mode_with_multiple_modes() -> Result<(), ShellError> {
+        let values = vec![int(3), int(1), int(3), int(2), int(1), int(4)];
+
+        assert_eq!(
+            UntaggedValue::mode(&values)?,
+            UntaggedValue::table(&[int(1), int(3)])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn median_errors_on_non_numeric_values() {
+        assert!(UntaggedValue::median(&[string("a"), string("b"), string("c")]).is_err());
+        assert!(UntaggedValue::median(&[string("a"), string("b")]).is_err());
+    }
+
+    #[test]
+    fn median_and_mode_error_on_empty_input() {
+        assert!(UntaggedValue::median(&[]).is_err());
+        assert!(UntaggedValue::mode(&[]).is_err());
+    }
+
+    fn sample_data() -> Vec<Value> {
+        vec![2, 4, 4, 4, 5, 5, 7, 9].into_iter().map(int).collect()
+    }
+
+    #[test]
+    fn population_variance_and_stddev() -> Result<(), ShellError> {
+        // mean 5, squared deviations 9+1+1+1+0+0+4+16 = 32, 32 / 8 = 4
+        assert_eq!(
+            UntaggedValue::variance(&sample_data(), false)?,
+            decimal("4")
+        );
+        assert_eq!(UntaggedValue::stddev(&sample_data(), false)?, decimal("2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sample_variance_and_stddev() -> Result<(), ShellError> {
+        // 32 / 7 = 4.571428..., sqrt = 2.138089...
+        let variance = UntaggedValue::variance(&sample_data(), true)?;
+        let stddev = UntaggedValue::stddev(&sample_data(), true)?;
+
+        match (variance, stddev) {
+            (
+                UntaggedValue::Primitive(Primitive::Decimal(variance)),
+                UntaggedValue::Primitive(Primitive::Decimal(stddev)),
+            ) => {
+                assert_eq!(
+                    variance.with_scale(6),
+                    BigDecimal::from_str("4.571428").unwrap()
+                );
+                assert_eq!(
+                    stddev.with_scale(6),
+                    BigDecimal::from_str("2.138089").unwrap()
+                );
+            }
+            _ => unreachable!("variance and stddev are decimals"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn variance_errors_on_too_few_values() {
+        assert!(UntaggedValue::variance(&[], false).is_err());
+        assert!(UntaggedValue::variance(&[int(1)], true).is_err());
+        assert!(UntaggedValue::stddev(&[int(1)], false).is_ok());
+    }
+
+    #[test]
+    fn product_of_ints() -> Result<(), ShellError> {
+        let values = vec![int(2), int(3), int(4)];
+
+        assert_eq!(UntaggedValue::product(&values)?, UntaggedValue::int(24));
+        assert_eq!(UntaggedValue::product(&[])?, UntaggedValue::int(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn product_of_mixed_numbers() -> Result<(), ShellError> {
+        let values = vec![int(2), decimal("1.5").into_untagged_value(), int(3)];
+
+        assert_eq!(UntaggedValue::product(&values)?, decimal("9"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn product_errors_on_non_numeric_values() {
+        assert!(UntaggedValue::product(&[int(2), string("3")]).is_err());
+    }
+
+    #[test]
+    fn cumulative_sum_gives_running_totals() -> Result<(), ShellError> {
+        let values = vec![int(1), int(2), int(3)];
+
+        assert_eq!(
+            UntaggedValue::cumulative_sum(&values)?,
+            UntaggedValue::table(&[int(1), int(3), int(6)])
+        );
+        assert!(UntaggedValue::cumulative_sum(&[int(1), string("2")]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn histogram_errors_on_non_numeric_values() {
+        let table = vec![
+            row(vec![("size", int(1))]),
+            row(vec![("size", string("big"))]),
+        ];
+
+        assert!(UntaggedValue::histogram(&table, "size", 2).is_err());
+    }
+}
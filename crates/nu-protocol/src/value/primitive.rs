@@ -9,7 +9,9 @@ use nu_source::{PrettyDebug, Span, SpannedItem};
 use num_bigint::BigInt;
 use num_traits::cast::{FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// The most fundamental of structured values in Nu are the Primitive values. These values represent types like integers, strings, booleans, dates, etc that are then used
 /// as the buildig blocks to build up more complex structures.
@@ -55,7 +57,88 @@ pub enum Primitive {
     EndOfStream,
 }
 
+/// The unit to show filesizes in. `Auto` picks a decimal unit to suit the size, while the other units are
+/// either decimal (powers of 1000) or binary (powers of 1024)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FilesizeUnit {
+    Auto,
+    B,
+    Kb,
+    Mb,
+    Gb,
+    Tb,
+    Pb,
+    Kib,
+    Mib,
+    Gib,
+    Tib,
+    Pib,
+}
+
+impl FilesizeUnit {
+    /// The number of bytes in one of this unit, and the unit's label. `Auto` has no fixed size.
+    fn size_and_label(self) -> Option<(u64, &'static str)> {
+        match self {
+            FilesizeUnit::Auto => None,
+            FilesizeUnit::B => Some((1, "B")),
+            FilesizeUnit::Kb => Some((1000, "KB")),
+            FilesizeUnit::Mb => Some((1000u64.pow(2), "MB")),
+            FilesizeUnit::Gb => Some((1000u64.pow(3), "GB")),
+            FilesizeUnit::Tb => Some((1000u64.pow(4), "TB")),
+            FilesizeUnit::Pb => Some((1000u64.pow(5), "PB")),
+            FilesizeUnit::Kib => Some((1024, "KiB")),
+            FilesizeUnit::Mib => Some((1024u64.pow(2), "MiB")),
+            FilesizeUnit::Gib => Some((1024u64.pow(3), "GiB")),
+            FilesizeUnit::Tib => Some((1024u64.pow(4), "TiB")),
+            FilesizeUnit::Pib => Some((1024u64.pow(5), "PiB")),
+        }
+    }
+}
+
 impl Primitive {
+    /// Format a filesize in the given unit, with one decimal place for anything larger than bytes. `Auto` uses
+    /// the largest decimal unit that keeps the number at least 1. Other primitives are formatted as usual.
+    pub fn format_filesize(&self, unit: FilesizeUnit) -> String {
+        let bytes = match self {
+            Primitive::Bytes(bytes) => *bytes,
+            other => return format_primitive(other, None),
+        };
+
+        let unit = match unit {
+            FilesizeUnit::Auto => [
+                FilesizeUnit::Pb,
+                FilesizeUnit::Tb,
+                FilesizeUnit::Gb,
+                FilesizeUnit::Mb,
+                FilesizeUnit::Kb,
+            ]
+            .iter()
+            .copied()
+            .find(|unit| match unit.size_and_label() {
+                Some((size, _)) => bytes >= size,
+                None => false,
+            })
+            .unwrap_or(FilesizeUnit::B),
+            unit => unit,
+        };
+
+        match unit.size_and_label() {
+            Some((size, label)) if size > 1 => {
+                format!("{:.1} {}", bytes as f64 / size as f64, label)
+            }
+            _ => format!("{} B", bytes),
+        }
+    }
+
+    /// Format a duration as text like `2hr 3min 4sec`, leaving out any parts that are zero. Other primitives are
+    /// formatted as usual.
+    pub fn format_duration(&self) -> String {
+        match self {
+            Primitive::Duration(secs) => format_duration(*secs),
+            other => format_primitive(other, None),
+        }
+    }
+
     /// Converts a primitive value to a u64, if possible. Uses a span to build an error if the conversion isn't possible.
     pub fn as_u64(&self, span: Span) -> Result<u64, ShellError> {
         match self {
@@ -73,6 +156,45 @@ impl Primitive {
             )),
         }
     }
+
+    /// Compare two primitives. Numbers of different types (integers, decimals and bytes) are compared by value,
+    /// as are strings and lines. If the primitives can't be compared, the names of both types are returned.
+    pub fn compare(&self, other: &Primitive) -> Result<Ordering, (&'static str, &'static str)> {
+        match (self, other) {
+            (Primitive::Int(left), Primitive::Int(right)) => Ok(left.cmp(right)),
+            (Primitive::Bytes(left), Primitive::Bytes(right)) => Ok(left.cmp(right)),
+            (Primitive::Boolean(left), Primitive::Boolean(right)) => Ok(left.cmp(right)),
+            (Primitive::Date(left), Primitive::Date(right)) => Ok(left.cmp(right)),
+            (Primitive::Duration(left), Primitive::Duration(right)) => Ok(left.cmp(right)),
+            (Primitive::Path(left), Primitive::Path(right)) => Ok(left.cmp(right)),
+            (Primitive::Nothing, Primitive::Nothing) => Ok(Ordering::Equal),
+            (left, right) => match (left.text(), right.text()) {
+                (Some(left), Some(right)) => Ok(left.cmp(right)),
+                _ => match (left.as_decimal(), right.as_decimal()) {
+                    (Some(left), Some(right)) => Ok(left.cmp(&right)),
+                    _ => Err((self.type_name(), other.type_name())),
+                },
+            },
+        }
+    }
+
+    fn as_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            Primitive::Int(int) => Some(BigDecimal::new(int.clone(), 0)),
+            Primitive::Decimal(decimal) => Some(decimal.clone()),
+            Primitive::Bytes(bytes) => Some(BigDecimal::new(BigInt::from(*bytes), 0)),
+            _ => None,
+        }
+    }
+
+    /// Get the raw text of a String or Line primitive. Unlike `Value::as_string`, no newline is added for a Line.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Primitive::String(string) => Some(&string[..]),
+            Primitive::Line(line) => Some(&line[..]),
+            _ => None,
+        }
+    }
 }
 
 impl From<BigDecimal> for Primitive {
@@ -141,11 +263,7 @@ pub fn format_primitive(primitive: &Primitive, field_name: Option<&String>) -> S
         Primitive::Duration(sec) => format_duration(*sec),
         Primitive::Int(i) => i.to_string(),
         Primitive::Decimal(decimal) => format!("{:.4}", decimal),
-        Primitive::Range(range) => format!(
-            "{}..{}",
-            format_primitive(&range.from.0.item, None),
-            format_primitive(&range.to.0.item, None)
-        ),
+        Primitive::Range(range) => range.to_string(),
         Primitive::Pattern(s) => s.to_string(),
         Primitive::String(s) => s.to_owned(),
         Primitive::Line(s) => s.to_owned(),
@@ -181,18 +299,37 @@ pub fn format_primitive(primitive: &Primitive, field_name: Option<&String>) -> S
     }
 }
 
-/// Format a duration in seconds into a string
+/// Format a number of seconds into a string like `2hr 3min 4sec`, leaving out any parts that are zero. A zero
+/// duration is shown as `0sec`.
 pub fn format_duration(sec: u64) -> String {
-    let (minutes, seconds) = (sec / 60, sec % 60);
-    let (hours, minutes) = (minutes / 60, minutes % 60);
-    let (days, hours) = (hours / 24, hours % 24);
-
-    match (days, hours, minutes, seconds) {
-        (0, 0, 0, 1) => "1 sec".to_owned(),
-        (0, 0, 0, s) => format!("{} secs", s),
-        (0, 0, m, s) => format!("{}:{:02}", m, s),
-        (0, h, m, s) => format!("{}:{:02}:{:02}", h, m, s),
-        (d, h, m, s) => format!("{}:{:02}:{:02}:{:02}", d, h, m, s),
+    format_precise_duration(Duration::from_secs(sec))
+}
+
+/// Format a duration like `format_duration`, also showing parts of a second in `ms`, `us` and `ns`
+pub fn format_precise_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let nanos = u64::from(duration.subsec_nanos());
+
+    let parts = [
+        (secs / 86_400, "day"),
+        (secs / 3_600 % 24, "hr"),
+        (secs / 60 % 60, "min"),
+        (secs % 60, "sec"),
+        (nanos / 1_000_000, "ms"),
+        (nanos / 1_000 % 1_000, "us"),
+        (nanos % 1_000, "ns"),
+    ];
+
+    let text: Vec<String> = parts
+        .iter()
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(amount, unit)| format!("{}{}", amount, unit))
+        .collect();
+
+    if text.is_empty() {
+        "0sec".to_string()
+    } else {
+        text.join(" ")
     }
 }
 
@@ -260,3 +397,120 @@ pub fn format_date(d: &DateTime<Utc>) -> String {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{format_duration, format_precise_duration, format_primitive, FilesizeUnit};
+    use crate::{Primitive, UntaggedValue};
+    use nu_errors::ShellError;
+    use std::cmp::Ordering;
+    use std::time::Duration;
+
+    #[test]
+    fn text_of_a_line_has_no_newline() -> Result<(), ShellError> {
+        let line = Primitive::Line("nushell".to_string());
+
+        assert_eq!(line.text(), Some("nushell"));
+        assert_eq!(
+            UntaggedValue::from(line)
+                .into_untagged_value()
+                .as_string()?,
+            "nushell\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn format_filesize_picks_a_unit_automatically() {
+        assert_eq!(
+            Primitive::Bytes(999).format_filesize(FilesizeUnit::Auto),
+            "999 B"
+        );
+        assert_eq!(
+            Primitive::Bytes(1500).format_filesize(FilesizeUnit::Auto),
+            "1.5 KB"
+        );
+        assert_eq!(
+            Primitive::Bytes(2_500_000_000).format_filesize(FilesizeUnit::Auto),
+            "2.5 GB"
+        );
+    }
+
+    #[test]
+    fn format_filesize_in_a_forced_unit() {
+        let size = Primitive::Bytes(1_572_864);
+
+        assert_eq!(size.format_filesize(FilesizeUnit::Mib), "1.5 MiB");
+        assert_eq!(size.format_filesize(FilesizeUnit::Kb), "1572.9 KB");
+        assert_eq!(size.format_filesize(FilesizeUnit::B), "1572864 B");
+    }
+
+    #[test]
+    fn format_duration_with_several_units() {
+        assert_eq!(
+            Primitive::Duration(2 * 3600 + 3 * 60 + 4).format_duration(),
+            "2hr 3min 4sec"
+        );
+        assert_eq!(format_duration(86_400 + 60), "1day 1min");
+    }
+
+    #[test]
+    fn format_duration_shorter_than_a_second() {
+        assert_eq!(format_precise_duration(Duration::from_millis(250)), "250ms");
+        assert_eq!(
+            format_precise_duration(Duration::from_nanos(1_500)),
+            "1us 500ns"
+        );
+        assert_eq!(
+            format_precise_duration(Duration::new(1, 5_000_000)),
+            "1sec 5ms"
+        );
+        assert_eq!(Primitive::Duration(0).format_duration(), "0sec");
+    }
+
+    #[test]
+    fn durations_display_with_units() {
+        assert_eq!(
+            format_primitive(&Primitive::Duration(3 * 60 + 4), None),
+            "3min 4sec"
+        );
+    }
+
+    #[test]
+    fn compares_numbers_of_different_types() {
+        let one = Primitive::Int(1.into());
+        let one_and_a_half = Primitive::from(1.5);
+
+        assert_eq!(one.compare(&one_and_a_half), Ok(Ordering::Less));
+        assert_eq!(one_and_a_half.compare(&one), Ok(Ordering::Greater));
+        assert_eq!(one.compare(&Primitive::Bytes(1)), Ok(Ordering::Equal));
+    }
+
+    #[test]
+    fn compares_strings_and_lines() {
+        let string = Primitive::String("andres".to_string());
+        let line = Primitive::Line("jonathan".to_string());
+
+        assert_eq!(string.compare(&line), Ok(Ordering::Less));
+    }
+
+    #[test]
+    fn compare_errors_on_incompatible_types() {
+        let string = Primitive::String("1".to_string());
+
+        assert_eq!(
+            string.compare(&Primitive::Int(1.into())),
+            Err(("string", "integer"))
+        );
+    }
+
+    #[test]
+    fn text_of_a_string_is_the_string() {
+        assert_eq!(
+            Primitive::String("nushell".to_string()).text(),
+            Some("nushell")
+        );
+        assert_eq!(Primitive::Boolean(true).text(), None);
+    }
+}
@@ -1,7 +1,12 @@
+use crate::type_name::ShellTypeName;
+use crate::value::primitive::format_primitive;
 use crate::value::Primitive;
 use derive_new::new;
-use nu_source::{b, DebugDocBuilder, Spanned};
+use nu_errors::{ExpectedRange, ShellError};
+use nu_source::{b, DebugDocBuilder, Spanned, SpannedItem};
+use num_traits::cast::ToPrimitive;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
 /// The two types of ways to include a range end. Inclusive means to include the value (eg 1..3 inclusive would include the 3 value).
 /// Exclusive excludes the value (eg 1..3 exclusive does not include 3 value)
@@ -35,3 +40,165 @@ pub struct Range {
     pub from: (Spanned<Primitive>, RangeInclusion),
     pub to: (Spanned<Primitive>, RangeInclusion),
 }
+
+impl Range {
+    /// Resolve the range into start (inclusive) and end (exclusive) indices into a sequence of the given length.
+    /// Negative bounds count from the end of the sequence, a Nothing bound leaves that end of the range open, and
+    /// bounds past either end of the sequence are clamped.
+    pub(crate) fn indices_for_length(&self, len: usize) -> Result<(usize, usize), ShellError> {
+        let (from, from_inclusion) = &self.from;
+        let (to, to_inclusion) = &self.to;
+
+        let start = match bound_index(from, len)? {
+            None => 0,
+            Some(start) => match from_inclusion {
+                RangeInclusion::Inclusive => start,
+                RangeInclusion::Exclusive => start + 1,
+            },
+        };
+
+        let end = match bound_index(to, len)? {
+            None => len as i64,
+            Some(end) => match to_inclusion {
+                RangeInclusion::Inclusive => end + 1,
+                RangeInclusion::Exclusive => end,
+            },
+        };
+
+        let clamp = |index: i64| index.max(0).min(len as i64) as usize;
+
+        let (start, end) = (clamp(start), clamp(end));
+
+        Ok((start, end.max(start)))
+    }
+
+    /// Check whether a value falls between the bounds of the range. A Nothing bound leaves that end of the range
+    /// open, and values that can't be compared with a bound are not in the range.
+    pub fn contains(&self, value: &Primitive) -> bool {
+        let (from, from_inclusion) = &self.from;
+        let (to, to_inclusion) = &self.to;
+
+        let above_start = match (&from.item, value.compare(&from.item)) {
+            (Primitive::Nothing, _) => true,
+            (_, Ok(Ordering::Greater)) => true,
+            (_, Ok(Ordering::Equal)) => *from_inclusion == RangeInclusion::Inclusive,
+            _ => false,
+        };
+
+        let below_end = match (&to.item, value.compare(&to.item)) {
+            (Primitive::Nothing, _) => true,
+            (_, Ok(Ordering::Less)) => true,
+            (_, Ok(Ordering::Equal)) => *to_inclusion == RangeInclusion::Inclusive,
+            _ => false,
+        };
+
+        above_start && below_end
+    }
+}
+
+impl std::fmt::Display for Range {
+    /// Format the range as `left..right`, or `left..=right` if the right end is inclusive. A Nothing bound is
+    /// left out, giving an open-ended range such as `1..`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (left, _) = &self.from;
+        let (right, right_inclusion) = &self.to;
+
+        let op = match (&right.item, right_inclusion) {
+            (Primitive::Nothing, _) | (_, RangeInclusion::Exclusive) => "..",
+            (_, RangeInclusion::Inclusive) => "..=",
+        };
+
+        write!(
+            f,
+            "{}{}{}",
+            format_primitive(&left.item, None),
+            op,
+            format_primitive(&right.item, None)
+        )
+    }
+}
+
+fn bound_index(bound: &Spanned<Primitive>, len: usize) -> Result<Option<i64>, ShellError> {
+    match &bound.item {
+        Primitive::Nothing => Ok(None),
+        Primitive::Int(int) => match int.to_i64() {
+            Some(index) if index < 0 => Ok(Some(len as i64 + index)),
+            Some(index) => Ok(Some(index)),
+            None => Err(ShellError::range_error(
+                ExpectedRange::I64,
+                &format!("{}", int).spanned(bound.span),
+                "converting a range bound into an index",
+            )),
+        },
+        other => Err(ShellError::type_error(
+            "integer",
+            other.type_name().spanned(bound.span),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Primitive, Range, RangeInclusion};
+    use nu_source::{Spanned, SpannedItem};
+
+    fn bound(
+        primitive: Primitive,
+        inclusion: RangeInclusion,
+    ) -> (Spanned<Primitive>, RangeInclusion) {
+        (primitive.spanned_unknown(), inclusion)
+    }
+
+    fn int(input: i64) -> Primitive {
+        Primitive::Int(input.into())
+    }
+
+    #[test]
+    fn contains_honors_bound_inclusion() {
+        let range = Range::new(
+            bound(int(1), RangeInclusion::Inclusive),
+            bound(int(5), RangeInclusion::Exclusive),
+        );
+
+        assert!(range.contains(&int(1)));
+        assert!(range.contains(&Primitive::from(4.5)));
+        assert!(!range.contains(&int(5)));
+        assert!(!range.contains(&int(0)));
+        assert!(!range.contains(&Primitive::String("3".into())));
+    }
+
+    #[test]
+    fn displays_exclusive_range() {
+        let range = Range::new(
+            bound(int(1), RangeInclusion::Inclusive),
+            bound(int(5), RangeInclusion::Exclusive),
+        );
+
+        assert_eq!(range.to_string(), "1..5");
+    }
+
+    #[test]
+    fn displays_inclusive_range() {
+        let range = Range::new(
+            bound(int(1), RangeInclusion::Inclusive),
+            bound(int(5), RangeInclusion::Inclusive),
+        );
+
+        assert_eq!(range.to_string(), "1..=5");
+    }
+
+    #[test]
+    fn displays_open_ended_ranges() {
+        let from = Range::new(
+            bound(int(1), RangeInclusion::Inclusive),
+            bound(Primitive::Nothing, RangeInclusion::Inclusive),
+        );
+        let to = Range::new(
+            bound(Primitive::Nothing, RangeInclusion::Inclusive),
+            bound(int(5), RangeInclusion::Exclusive),
+        );
+
+        assert_eq!(from.to_string(), "1..");
+        assert_eq!(to.to_string(), "..5");
+    }
+}
@@ -0,0 +1,677 @@
+use crate::type_name::{ShellTypeName, SpannedTypeName};
+use crate::value::column_path::{ColumnPath, UnspannedPathMember};
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::{UntaggedValue, Value};
+use indexmap::IndexMap;
+use nu_errors::ShellError;
+use num_traits::cast::ToPrimitive;
+
+impl Value {
+    /// Describe how two rows differ. Every column that changed, was added or was removed gets a row with
+    /// `change` ("changed", "added" or "removed"), `before` and `after` columns. Columns that are equal are left
+    /// out, so identical rows give an empty row. Values that aren't rows are compared as a `<value>` column.
+    pub fn diff(&self, other: &Value) -> Value {
+        let before = columns_of(self);
+        let after = columns_of(other);
+        let mut diff = TaggedDictBuilder::new(&self.tag);
+
+        for (key, old) in before.iter() {
+            match after.get(key) {
+                Some(new) if old.equals_ignoring_tags(new) => {}
+                Some(new) => diff.insert_value(key.clone(), change("changed", old, new)),
+                None => diff.insert_value(key.clone(), change("removed", old, &nothing())),
+            }
+        }
+
+        for (key, new) in after.iter() {
+            if !before.contains_key(key) {
+                diff.insert_value(key.clone(), change("added", &nothing(), new));
+            }
+        }
+
+        diff.into_value()
+    }
+
+    /// Overlay `other` on top of this row. Where both rows have a nested row under the same key the nested rows
+    /// are merged key by key, otherwise the value from `other` wins. Tables are replaced, not merged.
+    pub fn merge_deep(&self, other: &Value) -> Result<Value, ShellError> {
+        match (&self.value, &other.value) {
+            (UntaggedValue::Row(_), UntaggedValue::Row(_)) => Ok(merge_rows(self, other)),
+            (UntaggedValue::Row(_), _) => {
+                Err(ShellError::type_error("row", other.spanned_type_name()))
+            }
+            _ => Err(ShellError::type_error("row", self.spanned_type_name())),
+        }
+    }
+
+    /// Build a new row from the values at each of the paths. Each value is stored under the last member of its
+    /// path, with a numeric suffix added if that name is already taken. Paths that can't be followed give
+    /// Nothing.
+    pub fn select_paths(&self, paths: &[ColumnPath]) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Row(_) => {}
+            _ => return Err(ShellError::type_error("row", self.spanned_type_name())),
+        }
+
+        let mut selected = TaggedDictBuilder::new(&self.tag);
+        let mut taken: Vec<String> = vec![];
+
+        for path in paths {
+            let (last, _) = path.split_last().ok_or_else(|| {
+                ShellError::labeled_error(
+                    "Empty column path",
+                    "cannot select an empty path",
+                    &self.tag,
+                )
+            })?;
+
+            let name = match &last.unspanned {
+                UnspannedPathMember::String(string) => string.clone(),
+                UnspannedPathMember::Int(int) => format!("{}", int),
+            };
+
+            let mut key = name.clone();
+            let mut suffix = 1;
+            while taken.contains(&key) {
+                key = format!("{}_{}", name, suffix);
+                suffix += 1;
+            }
+
+            let value = follow_path(self, path).unwrap_or_else(nothing);
+            selected.insert_value(key.clone(), value);
+            taken.push(key);
+        }
+
+        Ok(selected.into_value())
+    }
+
+    /// Rename every column of a row, or of each row in a table, with `f`. Column order is kept. Renaming two
+    /// columns of the same row to the same name is an error.
+    pub fn rename_columns_with(&self, f: impl Fn(&str) -> String) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Row(_) => rename_row(self, &f),
+            UntaggedValue::Table(table) => {
+                let rows = table
+                    .iter()
+                    .map(|row| match &row.value {
+                        UntaggedValue::Row(_) => rename_row(row, &f),
+                        _ => Ok(row.clone()),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(UntaggedValue::Table(rows).into_value(&self.tag))
+            }
+            _ => Err(ShellError::type_error("row", self.spanned_type_name())),
+        }
+    }
+
+    /// Move a column of a row, or of each row in a table, so that it sits just before or just after another
+    /// column. With neither `before` nor `after` the column moves to the front. Missing columns are an error.
+    pub fn move_column(
+        &self,
+        column: &str,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Value, ShellError> {
+        match &self.value {
+            UntaggedValue::Row(_) => move_in_row(self, column, before, after),
+            UntaggedValue::Table(table) => {
+                let rows = table
+                    .iter()
+                    .map(|row| move_in_row(row, column, before, after))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(UntaggedValue::Table(rows).into_value(&self.tag))
+            }
+            _ => Err(ShellError::type_error("row", self.spanned_type_name())),
+        }
+    }
+
+    /// Put the Value under `column`. A table gets one row per element, with each element under the column,
+    /// and anything else gives a single-row table.
+    pub fn wrap(&self, column: &str) -> Value {
+        let wrap_one = |value: &Value| {
+            let mut row = TaggedDictBuilder::new(&value.tag);
+            row.insert_value(column, value.clone());
+            row.into_value()
+        };
+
+        let rows = match &self.value {
+            UntaggedValue::Table(table) => table.iter().map(wrap_one).collect(),
+            _ => vec![wrap_one(self)],
+        };
+
+        UntaggedValue::Table(rows).into_value(&self.tag)
+    }
+
+    /// Check that a row has exactly the columns named in the schema, each holding a value with the given type
+    /// name. The error lists every missing, extra and mistyped column.
+    pub fn validate_schema(&self, schema: &IndexMap<String, String>) -> Result<(), ShellError> {
+        let row = match &self.value {
+            UntaggedValue::Row(row) => row,
+            _ => return Err(ShellError::type_error("row", self.spanned_type_name())),
+        };
+
+        let mut problems = vec![];
+
+        let missing: Vec<&str> = schema
+            .keys()
+            .filter(|column| !row.contains_key(column))
+            .map(|column| &column[..])
+            .collect();
+        if !missing.is_empty() {
+            problems.push(format!("missing columns: {}", missing.join(", ")));
+        }
+
+        let extra: Vec<&str> = row
+            .keys()
+            .filter(|column| !schema.contains_key(*column))
+            .map(|column| &column[..])
+            .collect();
+        if !extra.is_empty() {
+            problems.push(format!("extra columns: {}", extra.join(", ")));
+        }
+
+        let mistyped: Vec<String> = schema
+            .iter()
+            .filter_map(|(column, expected)| {
+                let found = row.entries.get(column)?.type_name();
+
+                if found == expected.as_str() {
+                    None
+                } else {
+                    Some(format!(
+                        "{} (expected {}, found {})",
+                        column, expected, found
+                    ))
+                }
+            })
+            .collect();
+        if !mistyped.is_empty() {
+            problems.push(format!("mistyped columns: {}", mistyped.join(", ")));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ShellError::labeled_error(
+                "Row does not match the schema",
+                problems.join("; "),
+                &self.tag,
+            ))
+        }
+    }
+}
+
+fn move_in_row(
+    value: &Value,
+    column: &str,
+    before: Option<&str>,
+    after: Option<&str>,
+) -> Result<Value, ShellError> {
+    let unknown_column = |name: &str| {
+        ShellError::labeled_error(
+            "Unknown column",
+            format!("row does not contain a column named '{}'", name),
+            &value.tag,
+        )
+    };
+
+    let mut entries = match &value.value {
+        UntaggedValue::Row(row) => row.entries.clone(),
+        _ => return Err(ShellError::type_error("row", value.spanned_type_name())),
+    };
+
+    let (_, _, moved) = entries
+        .shift_remove_full(column)
+        .ok_or_else(|| unknown_column(column))?;
+
+    let position = match (before, after) {
+        (Some(_), Some(_)) => {
+            return Err(ShellError::labeled_error(
+                "Ambiguous move",
+                "a column can be moved before or after another column, but not both",
+                &value.tag,
+            ))
+        }
+        (Some(before), None) => entries
+            .get_full(before)
+            .map(|(index, _, _)| index)
+            .ok_or_else(|| unknown_column(before))?,
+        (None, Some(after)) => entries
+            .get_full(after)
+            .map(|(index, _, _)| index + 1)
+            .ok_or_else(|| unknown_column(after))?,
+        (None, None) => 0,
+    };
+
+    let mut reordered = IndexMap::with_capacity(entries.len() + 1);
+    let mut rest = entries.into_iter();
+
+    reordered.extend(rest.by_ref().take(position));
+    reordered.insert(column.to_string(), moved);
+    reordered.extend(rest);
+
+    Ok(UntaggedValue::row(reordered).into_value(&value.tag))
+}
+
+fn rename_row(value: &Value, f: &impl Fn(&str) -> String) -> Result<Value, ShellError> {
+    let mut entries = IndexMap::new();
+
+    if let UntaggedValue::Row(row) = &value.value {
+        for (key, column) in row.entries.iter() {
+            let renamed = f(key);
+
+            if entries.contains_key(&renamed) {
+                return Err(ShellError::labeled_error(
+                    "Column names collide after renaming",
+                    format!("more than one column renamed to '{}'", renamed),
+                    &value.tag,
+                ));
+            }
+
+            entries.insert(renamed, column.clone());
+        }
+    }
+
+    Ok(UntaggedValue::row(entries).into_value(&value.tag))
+}
+
+/// Follow a column path through rows and tables, giving None if any step of the path is missing
+fn follow_path(value: &Value, path: &ColumnPath) -> Option<Value> {
+    let mut current = value;
+
+    for member in path.iter() {
+        current = match (&current.value, &member.unspanned) {
+            (UntaggedValue::Row(row), UnspannedPathMember::String(column)) => {
+                row.entries.get(column)?
+            }
+            (UntaggedValue::Table(table), UnspannedPathMember::Int(index)) => {
+                table.get(index.to_usize()?)?
+            }
+            _ => return None,
+        };
+    }
+
+    Some(current.clone())
+}
+
+fn merge_rows(base: &Value, overlay: &Value) -> Value {
+    match (&base.value, &overlay.value) {
+        (UntaggedValue::Row(base_row), UntaggedValue::Row(overlay_row)) => {
+            let mut merged = base_row.clone();
+
+            for (key, value) in overlay_row.entries.iter() {
+                let value = match base_row.entries.get(key) {
+                    Some(existing) => merge_rows(existing, value),
+                    None => value.clone(),
+                };
+
+                merged.insert_data_at_key(key, value);
+            }
+
+            UntaggedValue::Row(merged).into_value(&base.tag)
+        }
+        _ => overlay.clone(),
+    }
+}
+
+fn columns_of(value: &Value) -> IndexMap<String, Value> {
+    match &value.value {
+        UntaggedValue::Row(row) => row.entries.clone(),
+        _ => {
+            let mut entries = IndexMap::new();
+            entries.insert("<value>".to_string(), value.clone());
+            entries
+        }
+    }
+}
+
+fn change(kind: &str, before: &Value, after: &Value) -> Value {
+    let mut row = TaggedDictBuilder::new(&before.tag);
+    row.insert_untagged("change", UntaggedValue::string(kind));
+    row.insert_value("before", before.clone());
+    row.insert_value("after", after.clone());
+    row.into_value()
+}
+
+fn nothing() -> Value {
+    UntaggedValue::nothing().into_untagged_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{int, nothing, row, string};
+    use crate::{ColumnPath, PathMember, UntaggedValue, Value};
+    use indexmap::IndexMap;
+    use nu_errors::ShellError;
+    use nu_source::Span;
+
+    fn change(kind: &str, before: Value, after: Value) -> Value {
+        row(vec![
+            ("change", string(kind)),
+            ("before", before),
+            ("after", after),
+        ])
+    }
+
+    fn path(members: &[&str]) -> ColumnPath {
+        ColumnPath::new(
+            members
+                .iter()
+                .map(|member| PathMember::string(*member, Span::unknown()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn select_paths_from_a_nested_row() -> Result<(), ShellError> {
+        let value = row(vec![
+            ("a", row(vec![("b", int(1)), ("z", int(2))])),
+            ("c", string("three")),
+        ]);
+
+        let selected = value.select_paths(&[path(&["a", "b"]), path(&["c"])])?;
+
+        assert!(selected.equals_ignoring_tags(&row(vec![("b", int(1)), ("c", string("three"))])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn select_paths_suffixes_collisions_and_fills_missing_paths() -> Result<(), ShellError> {
+        let value = row(vec![
+            ("a", row(vec![("b", int(1))])),
+            ("c", row(vec![("b", int(2))])),
+        ]);
+
+        let selected =
+            value.select_paths(&[path(&["a", "b"]), path(&["c", "b"]), path(&["a", "x"])])?;
+
+        assert!(selected.equals_ignoring_tags(&row(vec![
+            ("b", int(1)),
+            ("b_1", int(2)),
+            ("x", nothing())
+        ])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rename_columns_with_a_prefix() -> Result<(), ShellError> {
+        let table = UntaggedValue::table(&[
+            row(vec![("name", string("nu")), ("age", int(1))]),
+            row(vec![("name", string("bash")), ("age", int(30))]),
+        ])
+        .into_untagged_value();
+
+        let renamed = table.rename_columns_with(|column| format!("shell_{}", column))?;
+
+        let expected = UntaggedValue::table(&[
+            row(vec![("shell_name", string("nu")), ("shell_age", int(1))]),
+            row(vec![("shell_name", string("bash")), ("shell_age", int(30))]),
+        ])
+        .into_untagged_value();
+
+        assert!(renamed.equals_ignoring_tags(&expected));
+
+        if let UntaggedValue::Table(rows) = &renamed.value {
+            for row in rows {
+                assert_eq!(
+                    row.data_descriptors(),
+                    vec!["shell_name".to_string(), "shell_age".to_string()]
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn rename_columns_with_errors_on_collisions() {
+        let value = row(vec![("Name", string("nu")), ("name", string("bash"))]);
+
+        assert!(value
+            .rename_columns_with(|column| column.to_lowercase())
+            .is_err());
+    }
+
+    fn abc() -> Value {
+        row(vec![("a", int(1)), ("b", int(2)), ("c", int(3))])
+    }
+
+    #[test]
+    fn move_column_to_the_front() -> Result<(), ShellError> {
+        let moved = abc().move_column("c", None, None)?;
+
+        assert_eq!(moved.data_descriptors(), vec!["c", "a", "b"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn move_column_relative_to_another() -> Result<(), ShellError> {
+        assert_eq!(
+            abc().move_column("a", None, Some("b"))?.data_descriptors(),
+            vec!["b", "a", "c"]
+        );
+        assert_eq!(
+            abc().move_column("c", Some("b"), None)?.data_descriptors(),
+            vec!["a", "c", "b"]
+        );
+
+        let table = UntaggedValue::table(&[abc(), abc()]).into_untagged_value();
+
+        match table.move_column("a", None, Some("c"))?.value {
+            UntaggedValue::Table(rows) => {
+                for row in rows {
+                    assert_eq!(row.data_descriptors(), vec!["b", "c", "a"]);
+                }
+            }
+            _ => panic!("expected a table"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn move_column_errors_on_missing_columns() {
+        assert!(abc().move_column("z", None, None).is_err());
+        assert!(abc().move_column("a", Some("z"), None).is_err());
+    }
+
+    #[test]
+    fn wrap_a_primitive() {
+        let wrapped = int(1).wrap("value");
+
+        assert_eq!(
+            wrapped,
+            UntaggedValue::table(&[row(vec![("value", int(1))])]).into_untagged_value()
+        );
+    }
+
+    #[test]
+    fn wrap_a_table() {
+        let table = UntaggedValue::table(&[string("a"), string("b")]).into_untagged_value();
+
+        assert_eq!(
+            table.wrap("name"),
+            UntaggedValue::table(&[
+                row(vec![("name", string("a"))]),
+                row(vec![("name", string("b"))]),
+            ])
+            .into_untagged_value()
+        );
+    }
+
+    fn schema(columns: &[(&str, &str)]) -> IndexMap<String, String> {
+        columns
+            .iter()
+            .map(|(column, type_name)| ((*column).to_string(), (*type_name).to_string()))
+            .collect()
+    }
+
+    fn person() -> Value {
+        row(vec![("name", string("nu")), ("age", int(1))])
+    }
+
+    #[test]
+    fn validate_schema_of_a_conforming_row() -> Result<(), ShellError> {
+        person().validate_schema(&schema(&[("name", "string"), ("age", "integer")]))
+    }
+
+    fn schema_problems(schema: &IndexMap<String, String>) -> String {
+        let error = person()
+            .validate_schema(schema)
+            .expect_err("row should not match the schema")
+            .into_diagnostic();
+
+        error.labels[0].message.clone().unwrap_or_default()
+    }
+
+    #[test]
+    fn validate_schema_with_a_missing_column() {
+        let schema = schema(&[("name", "string"), ("age", "integer"), ("email", "string")]);
+
+        assert_eq!(schema_problems(&schema), "missing columns: email");
+    }
+
+    #[test]
+    fn validate_schema_with_an_extra_column() {
+        let schema = schema(&[("name", "string")]);
+
+        assert_eq!(schema_problems(&schema), "extra columns: age");
+    }
+
+    #[test]
+    fn validate_schema_with_a_mistyped_column() {
+        let schema = schema(&[("name", "string"), ("age", "string")]);
+
+        assert_eq!(
+            schema_problems(&schema),
+            "mistyped columns: age (expected string, found integer)"
+        );
+    }
+
+    #[test]
+    fn validate_schema_lists_every_problem() {
+        let schema = schema(&[("name", "integer"), ("email", "string")]);
+
+        assert_eq!(
+            schema_problems(&schema),
+            "missing columns: email; extra columns: age; mistyped columns: name (expected integer, found string)"
+        );
+    }
+
+    #[test]
+    fn merge_deep_overlays_nested_rows() -> Result<(), ShellError> {
+        let base = row(vec![
+            ("name", string("nu")),
+            (
+                "config",
+                row(vec![
+                    ("color", string("blue")),
+                    (
+                        "history",
+                        row(vec![("size", int(100)), ("dedup", string("no"))]),
+                    ),
+                ]),
+            ),
+        ]);
+        let overlay = row(vec![(
+            "config",
+            row(vec![
+                ("history", row(vec![("size", int(500))])),
+                ("prompt", string(">")),
+            ]),
+        )]);
+
+        let expected = row(vec![
+            ("name", string("nu")),
+            (
+                "config",
+                row(vec![
+                    ("color", string("blue")),
+                    (
+                        "history",
+                        row(vec![("size", int(500)), ("dedup", string("no"))]),
+                    ),
+                    ("prompt", string(">")),
+                ]),
+            ),
+        ]);
+
+        assert!(base.merge_deep(&overlay)?.equals_ignoring_tags(&expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_deep_replaces_tables_and_scalars() -> Result<(), ShellError> {
+        let base = row(vec![
+            (
+                "plugins",
+                UntaggedValue::table(&[string("a"), string("b")]).into_untagged_value(),
+            ),
+            ("theme", row(vec![("name", string("dark"))])),
+        ]);
+        let overlay = row(vec![
+            (
+                "plugins",
+                UntaggedValue::table(&[string("c")]).into_untagged_value(),
+            ),
+            ("theme", string("light")),
+        ]);
+
+        assert!(base.merge_deep(&overlay)?.equals_ignoring_tags(&overlay));
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_deep_errors_unless_both_values_are_rows() {
+        let value = row(vec![("name", string("nu"))]);
+
+        assert!(value.merge_deep(&int(1)).is_err());
+        assert!(int(1).merge_deep(&value).is_err());
+    }
+
+    #[test]
+    fn diff_of_a_changed_value() {
+        let before = row(vec![("name", string("nu")), ("version", int(10))]);
+        let after = row(vec![("name", string("nu")), ("version", int(11))]);
+
+        assert!(before
+            .diff(&after)
+            .equals_ignoring_tags(&row(vec![("version", change("changed", int(10), int(11)))])));
+    }
+
+    #[test]
+    fn diff_of_an_added_column() {
+        let before = row(vec![("name", string("nu"))]);
+        let after = row(vec![("name", string("nu")), ("shell", string("yes"))]);
+
+        assert!(before.diff(&after).equals_ignoring_tags(&row(vec![(
+            "shell",
+            change("added", nothing(), string("yes"))
+        )])));
+    }
+
+    #[test]
+    fn diff_of_a_removed_column() {
+        let before = row(vec![("name", string("nu")), ("shell", string("yes"))]);
+        let after = row(vec![("name", string("nu"))]);
+
+        assert!(before.diff(&after).equals_ignoring_tags(&row(vec![(
+            "shell",
+            change("removed", string("yes"), nothing())
+        )])));
+    }
+
+    #[test]
+    fn diff_of_identical_rows_is_empty() {
+        let value = row(vec![("name", string("nu"))]);
+
+        assert!(value.diff(&value).equals_ignoring_tags(&row(vec![])));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::value::column_path::{PathMember, UnspannedPathMember};
+use crate::value::primitive::Primitive;
+use crate::value::range::Range;
+use crate::value::{UntaggedValue, Value};
+use num_bigint::BigInt;
+use std::mem::size_of;
+
+impl Value {
+    /// Estimate the number of bytes the Value owns on the heap: string and buffer capacities, the slots of
+    /// tables and rows, and everything nested inside them. The Value itself is not counted, nor are errors,
+    /// blocks and tags. Use it to warn before building a huge table, not for exact accounting.
+    pub fn heap_size(&self) -> usize {
+        match &self.value {
+            UntaggedValue::Primitive(primitive) => primitive_heap_size(primitive),
+            UntaggedValue::Row(row) => {
+                let slot = size_of::<String>() + size_of::<Value>() + size_of::<u64>();
+
+                row.entries.capacity() * slot
+                    + row
+                        .entries
+                        .iter()
+                        .map(|(key, value)| key.capacity() + value.heap_size())
+                        .sum::<usize>()
+            }
+            UntaggedValue::Table(table) => {
+                table.capacity() * size_of::<Value>()
+                    + table.iter().map(|value| value.heap_size()).sum::<usize>()
+            }
+            UntaggedValue::Error(_) | UntaggedValue::Block(_) => 0,
+        }
+    }
+}
+
+fn primitive_heap_size(primitive: &Primitive) -> usize {
+    match primitive {
+        Primitive::Int(int) => bigint_heap_size(int),
+        Primitive::Decimal(decimal) => bigint_heap_size(&decimal.as_bigint_and_exponent().0),
+        Primitive::String(string) | Primitive::Line(string) | Primitive::Pattern(string) => {
+            string.capacity()
+        }
+        Primitive::ColumnPath(path) => {
+            path.members().capacity() * size_of::<PathMember>()
+                + path
+                    .members()
+                    .iter()
+                    .map(|member| match &member.unspanned {
+                        UnspannedPathMember::String(string) => string.capacity(),
+                        UnspannedPathMember::Int(int) => bigint_heap_size(int),
+                    })
+                    .sum::<usize>()
+        }
+        Primitive::Range(range) => {
+            size_of::<Range>()
+                + primitive_heap_size(&range.from.0.item)
+                + primitive_heap_size(&range.to.0.item)
+        }
+        Primitive::Path(path) => path.as_os_str().len(),
+        Primitive::Binary(bytes) => bytes.capacity(),
+        Primitive::Nothing
+        | Primitive::Bytes(_)
+        | Primitive::Boolean(_)
+        | Primitive::Date(_)
+        | Primitive::Duration(_)
+        | Primitive::BeginningOfStream
+        | Primitive::EndOfStream => 0,
+    }
+}
+
+fn bigint_heap_size(int: &BigInt) -> usize {
+    (int.bits() + 7) / 8
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{row, string, table};
+    use crate::Value;
+    use std::mem::size_of;
+
+    #[test]
+    fn heap_size_of_strings() {
+        assert_eq!(string("").heap_size(), 0);
+        assert_eq!(string("nushell").heap_size(), 7);
+        assert_eq!(string("x".repeat(1000)).heap_size(), 1000);
+    }
+
+    #[test]
+    fn heap_size_of_a_table_counts_its_slots_and_cells() {
+        let cells = vec![string("nu"), string("shell")];
+
+        assert_eq!(table(&cells).heap_size(), 2 * size_of::<Value>() + 7);
+    }
+
+    #[test]
+    fn heap_size_grows_with_nesting() {
+        let name = string("nushell");
+        let inner = row(vec![("name", name.clone())]);
+        let outer = table(&[inner.clone(), inner.clone()]);
+        let wrapped = row(vec![("rows", outer.clone()), ("name", name.clone())]);
+
+        assert!(inner.heap_size() > name.heap_size());
+        assert!(outer.heap_size() > 2 * inner.heap_size());
+        assert!(wrapped.heap_size() > outer.heap_size() + name.heap_size());
+    }
+}
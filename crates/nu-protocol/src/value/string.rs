@@ -0,0 +1,698 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::primitive::Primitive;
+use crate::value::range::Range;
+use crate::value::{UntaggedValue, Value};
+use bigdecimal::BigDecimal;
+use nu_errors::ShellError;
+use num_bigint::BigInt;
+use num_traits::Num;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Which side(s) of a string to trim
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TrimSide {
+    Both,
+    Left,
+    Right,
+}
+
+/// Which side of a string to add padding to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PadSide {
+    Left,
+    Right,
+}
+
+impl Value {
+    /// Check if a string value contains the given substring, returning a boolean value
+    pub fn str_contains(&self, needle: &str, insensitive: bool) -> Result<Value, ShellError> {
+        self.str_matches(needle, insensitive, |haystack, needle| {
+            haystack.contains(needle)
+        })
+    }
+
+    /// Check if a string value starts with the given prefix, returning a boolean value
+    pub fn str_starts_with(&self, prefix: &str, insensitive: bool) -> Result<Value, ShellError> {
+        self.str_matches(prefix, insensitive, |haystack, prefix| {
+            haystack.starts_with(prefix)
+        })
+    }
+
+    /// Check if a string value ends with the given suffix, returning a boolean value
+    pub fn str_ends_with(&self, suffix: &str, insensitive: bool) -> Result<Value, ShellError> {
+        self.str_matches(suffix, insensitive, |haystack, suffix| {
+            haystack.ends_with(suffix)
+        })
+    }
+
+    /// Trim a string value on the given side(s). Whitespace is trimmed unless a set of characters to trim is given
+    pub fn str_trim(&self, chars: Option<&str>, side: TrimSide) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        let trimmed = match (chars, side) {
+            (None, TrimSide::Both) => string.trim(),
+            (None, TrimSide::Left) => string.trim_start(),
+            (None, TrimSide::Right) => string.trim_end(),
+            (Some(chars), TrimSide::Both) => string.trim_matches(|c: char| chars.contains(c)),
+            (Some(chars), TrimSide::Left) => string.trim_start_matches(|c: char| chars.contains(c)),
+            (Some(chars), TrimSide::Right) => string.trim_end_matches(|c: char| chars.contains(c)),
+        };
+
+        Ok(UntaggedValue::string(trimmed).into_value(&self.tag))
+    }
+
+    /// Slice a string value by character indices. Negative indices count from the end of the string and
+    /// out-of-range bounds are clamped to the string
+    pub fn str_substring(&self, range: &Range) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+        let (start, end) = range.indices_for_length(string.chars().count())?;
+
+        let substring: String = string.chars().skip(start).take(end - start).collect();
+
+        Ok(UntaggedValue::string(substring).into_value(&self.tag))
+    }
+
+    /// Split a string value into a row with the given column names. Pieces beyond the number of columns stay
+    /// joined in the last column, and columns without a matching piece are set to Nothing
+    pub fn split_column(&self, separator: &str, names: &[String]) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+        let mut pieces = string.splitn(names.len(), separator);
+
+        let mut row = TaggedDictBuilder::new(&self.tag);
+
+        for name in names {
+            match pieces.next() {
+                Some(piece) => row.insert_untagged(name.clone(), UntaggedValue::string(piece)),
+                None => row.insert_untagged(name.clone(), UntaggedValue::nothing()),
+            }
+        }
+
+        Ok(row.into_value())
+    }
+
+    /// Split a string value into a table of strings. An empty separator splits the string into its characters
+    pub fn split_list(&self, separator: &str) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        let pieces: Vec<Value> = if separator.is_empty() {
+            string
+                .chars()
+                .map(|c| UntaggedValue::string(c.to_string()).into_value(&self.tag))
+                .collect()
+        } else {
+            string
+                .split(separator)
+                .map(|piece| UntaggedValue::string(piece).into_value(&self.tag))
+                .collect()
+        };
+
+        Ok(UntaggedValue::Table(pieces).into_value(&self.tag))
+    }
+
+    /// Split a string value into a table of lines, accepting both `\n` and `\r\n` line endings. A trailing
+    /// line ending does not produce an empty last line
+    pub fn lines(&self) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        let lines: Vec<Value> = string
+            .lines()
+            .map(|line| UntaggedValue::line(line).into_value(&self.tag))
+            .collect();
+
+        Ok(UntaggedValue::Table(lines).into_value(&self.tag))
+    }
+
+    /// Convert a string value to uppercase using Unicode case mapping
+    pub fn str_to_upper(&self) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        Ok(UntaggedValue::string(string.to_uppercase()).into_value(&self.tag))
+    }
+
+    /// Convert a string value to lowercase using Unicode case mapping
+    pub fn str_to_lower(&self) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        Ok(UntaggedValue::string(string.to_lowercase()).into_value(&self.tag))
+    }
+
+    /// Capitalize a string value, uppercasing its first character and lowercasing the rest
+    pub fn str_capitalize(&self) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        Ok(UntaggedValue::string(capitalize(string)).into_value(&self.tag))
+    }
+
+    /// Capitalize every whitespace-delimited word of a string value, keeping the whitespace as-is
+    pub fn str_title_case(&self) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        let mut title = String::with_capacity(string.len());
+        let mut word_start = 0;
+
+        for (idx, c) in string.char_indices() {
+            if c.is_whitespace() {
+                title.push_str(&capitalize(&string[word_start..idx]));
+                title.push(c);
+                word_start = idx + c.len_utf8();
+            }
+        }
+        title.push_str(&capitalize(&string[word_start..]));
+
+        Ok(UntaggedValue::string(title).into_value(&self.tag))
+    }
+
+    /// Pad a string value with the fill character on the given side until it is `width` characters long.
+    /// Strings that are already at least `width` characters long are left untouched
+    pub fn str_pad(&self, width: usize, fill: char, side: PadSide) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+        let len = string.chars().count();
+
+        if len >= width {
+            return Ok(UntaggedValue::string(string).into_value(&self.tag));
+        }
+
+        let padding: String = std::iter::repeat(fill).take(width - len).collect();
+
+        let padded = match side {
+            PadSide::Left => format!("{}{}", padding, string),
+            PadSide::Right => format!("{}{}", string, padding),
+        };
+
+        Ok(UntaggedValue::string(padded).into_value(&self.tag))
+    }
+
+    /// Reverse a string value by grapheme clusters, so combining characters stay attached to their base character
+    pub fn str_reverse(&self) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        let reversed: String = string.graphemes(true).rev().collect();
+
+        Ok(UntaggedValue::string(reversed).into_value(&self.tag))
+    }
+
+    /// Parse a string value into an integer using the given radix. A radix of 0 detects the radix from a
+    /// `0x`, `0o` or `0b` prefix, falling back to decimal
+    pub fn parse_int(&self, radix: u32) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+        let trimmed = string.trim();
+
+        let (negative, digits) = if trimmed.starts_with('-') {
+            (true, &trimmed[1..])
+        } else if trimmed.starts_with('+') {
+            (false, &trimmed[1..])
+        } else {
+            (false, trimmed)
+        };
+
+        let (radix, digits) = match radix {
+            0 => match digits.get(..2).map(|prefix| prefix.to_ascii_lowercase()) {
+                Some(prefix) if prefix == "0x" => (16, &digits[2..]),
+                Some(prefix) if prefix == "0o" => (8, &digits[2..]),
+                Some(prefix) if prefix == "0b" => (2, &digits[2..]),
+                _ => (10, digits),
+            },
+            2..=36 => (radix, digits),
+            _ => {
+                return Err(ShellError::labeled_error(
+                    "Invalid radix",
+                    format!("radix {} is not between 2 and 36", radix),
+                    &self.tag,
+                ))
+            }
+        };
+
+        // `from_str_radix` accepts a sign of its own, which would let a second sign through
+        let parsed = if digits.starts_with('-') || digits.starts_with('+') {
+            None
+        } else {
+            BigInt::from_str_radix(digits, radix).ok()
+        };
+
+        match parsed {
+            Some(int) if negative => Ok(UntaggedValue::int(-int).into_value(&self.tag)),
+            Some(int) => Ok(UntaggedValue::int(int).into_value(&self.tag)),
+            None => Err(ShellError::labeled_error(
+                "Could not parse as integer",
+                format!("'{}' is not a base {} integer", string, radix),
+                &self.tag,
+            )),
+        }
+    }
+
+    /// Parse a string value into a decimal
+    pub fn parse_float(&self) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        match BigDecimal::from_str(string.trim()) {
+            Ok(decimal) => Ok(UntaggedValue::decimal(decimal).into_value(&self.tag)),
+            Err(_) => Err(ShellError::labeled_error(
+                "Could not parse as decimal",
+                format!("'{}' is not a decimal", string),
+                &self.tag,
+            )),
+        }
+    }
+
+    fn str_matches(
+        &self,
+        pattern: &str,
+        insensitive: bool,
+        matches: impl Fn(&str, &str) -> bool,
+    ) -> Result<Value, ShellError> {
+        let string = self.expect_text()?;
+
+        let result = if insensitive {
+            matches(&string.to_lowercase(), &pattern.to_lowercase())
+        } else {
+            matches(string, pattern)
+        };
+
+        Ok(UntaggedValue::boolean(result).into_value(&self.tag))
+    }
+
+    /// Get the text of a String or Line, so that the output of `lines` and external commands can be worked on
+    /// like any other string
+    fn expect_text(&self) -> Result<&str, ShellError> {
+        match &self.value {
+            UntaggedValue::Primitive(Primitive::String(string))
+            | UntaggedValue::Primitive(Primitive::Line(string)) => Ok(string),
+            _ => Err(ShellError::type_error("string", self.spanned_type_name())),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(|c| c.to_lowercase()))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PadSide, TrimSide};
+    use crate::test_support::{boolean, int, line, string, table};
+    use crate::{Primitive, Range, RangeInclusion, TaggedDictBuilder, UntaggedValue, Value};
+    use bigdecimal::BigDecimal;
+    use nu_errors::ShellError;
+    use nu_source::{SpannedItem, Tag};
+    use std::str::FromStr;
+
+    fn range(from: i64, to: i64, to_inclusion: RangeInclusion) -> Range {
+        Range::new(
+            (
+                Primitive::Int(from.into()).spanned_unknown(),
+                RangeInclusion::Inclusive,
+            ),
+            (Primitive::Int(to.into()).spanned_unknown(), to_inclusion),
+        )
+    }
+
+    #[test]
+    fn contains_matches_substring() -> Result<(), ShellError> {
+        let value = string("nushell");
+
+        assert_eq!(value.str_contains("shell", false)?, boolean(true));
+        assert_eq!(value.str_starts_with("nu", false)?, boolean(true));
+        assert_eq!(value.str_ends_with("ell", false)?, boolean(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn contains_does_not_match_missing_substring() -> Result<(), ShellError> {
+        let value = string("nushell");
+
+        assert_eq!(value.str_contains("bash", false)?, boolean(false));
+        assert_eq!(value.str_starts_with("shell", false)?, boolean(false));
+        assert_eq!(value.str_ends_with("nu", false)?, boolean(false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn contains_honors_insensitive_flag() -> Result<(), ShellError> {
+        let value = string("NuShell");
+
+        assert_eq!(value.str_contains("shell", false)?, boolean(false));
+        assert_eq!(value.str_contains("shell", true)?, boolean(true));
+        assert_eq!(value.str_starts_with("NU", true)?, boolean(true));
+        assert_eq!(value.str_ends_with("SHELL", true)?, boolean(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn trim_removes_whitespace_by_default() -> Result<(), ShellError> {
+        let value = string("  \tnushell \n");
+
+        assert_eq!(value.str_trim(None, TrimSide::Both)?, string("nushell"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn trim_removes_custom_characters() -> Result<(), ShellError> {
+        let value = string("--=nu-shell=--");
+
+        assert_eq!(
+            value.str_trim(Some("-="), TrimSide::Both)?,
+            string("nu-shell")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn trim_only_one_side() -> Result<(), ShellError> {
+        let value = string("  nushell  ");
+
+        assert_eq!(value.str_trim(None, TrimSide::Left)?, string("nushell  "));
+        assert_eq!(value.str_trim(None, TrimSide::Right)?, string("  nushell"));
+        assert_eq!(
+            string("xxnuxx").str_trim(Some("x"), TrimSide::Right)?,
+            string("xxnu")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn substring_with_positive_range() -> Result<(), ShellError> {
+        let value = string("nushell");
+
+        assert_eq!(
+            value.str_substring(&range(2, 6, RangeInclusion::Inclusive))?,
+            string("shell")
+        );
+        assert_eq!(
+            value.str_substring(&range(0, 2, RangeInclusion::Exclusive))?,
+            string("nu")
+        );
+        assert_eq!(
+            value.str_substring(&range(2, 100, RangeInclusion::Exclusive))?,
+            string("shell")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn substring_with_negative_start() -> Result<(), ShellError> {
+        let value = string("nushell");
+
+        assert_eq!(
+            value.str_substring(&range(-5, -1, RangeInclusion::Inclusive))?,
+            string("shell")
+        );
+        assert_eq!(
+            value.str_substring(&range(-100, 1, RangeInclusion::Inclusive))?,
+            string("nu")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn substring_with_empty_result() -> Result<(), ShellError> {
+        let value = string("nushell");
+
+        assert_eq!(
+            value.str_substring(&range(4, 2, RangeInclusion::Inclusive))?,
+            string("")
+        );
+        assert_eq!(
+            value.str_substring(&range(3, 3, RangeInclusion::Exclusive))?,
+            string("")
+        );
+
+        Ok(())
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| (*name).to_string()).collect()
+    }
+
+    fn row(entries: Vec<(&str, UntaggedValue)>) -> Value {
+        let mut row = TaggedDictBuilder::new(Tag::unknown());
+
+        for (key, value) in entries {
+            row.insert_untagged(key, value);
+        }
+
+        row.into_value()
+    }
+
+    #[test]
+    fn split_column_with_exact_pieces() -> Result<(), ShellError> {
+        let value = string("andres,jonathan,yehuda");
+
+        assert_eq!(
+            value.split_column(",", &names(&["a", "b", "c"]))?,
+            row(vec![
+                ("a", UntaggedValue::string("andres")),
+                ("b", UntaggedValue::string("jonathan")),
+                ("c", UntaggedValue::string("yehuda")),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_column_with_too_few_pieces() -> Result<(), ShellError> {
+        let value = string("andres,jonathan");
+
+        assert_eq!(
+            value.split_column(",", &names(&["a", "b", "c"]))?,
+            row(vec![
+                ("a", UntaggedValue::string("andres")),
+                ("b", UntaggedValue::string("jonathan")),
+                ("c", UntaggedValue::nothing()),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_column_with_too_many_pieces() -> Result<(), ShellError> {
+        let value = string("andres,jonathan,yehuda");
+
+        assert_eq!(
+            value.split_column(",", &names(&["a", "b"]))?,
+            row(vec![
+                ("a", UntaggedValue::string("andres")),
+                ("b", UntaggedValue::string("jonathan,yehuda")),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_list_on_separator() -> Result<(), ShellError> {
+        let value = string("andres,,yehuda");
+
+        assert_eq!(
+            value.split_list(",")?,
+            table(&[string("andres"), string(""), string("yehuda")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_list_with_empty_separator_splits_characters() -> Result<(), ShellError> {
+        let value = string("nü");
+
+        assert_eq!(value.split_list("")?, table(&[string("n"), string("ü")]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn string_helpers_accept_lines() -> Result<(), ShellError> {
+        assert_eq!(line("nushell").str_contains("SHELL", true)?, boolean(true));
+        assert_eq!(line("nushell").str_starts_with("nu", false)?, boolean(true));
+        assert_eq!(line("  nu  ").str_trim(None, TrimSide::Both)?, string("nu"));
+        assert_eq!(line("nu").str_to_upper()?, string("NU"));
+        assert_eq!(line("nu").str_pad(4, '.', PadSide::Left)?, string("..nu"));
+        assert_eq!(line("ff").parse_int(16)?, int(255));
+
+        Ok(())
+    }
+
+    #[test]
+    fn lines_splits_crlf_input() -> Result<(), ShellError> {
+        let value = string("andres\r\njonathan\nyehuda");
+
+        assert_eq!(
+            value.lines()?,
+            table(&[line("andres"), line("jonathan"), line("yehuda")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn lines_drops_trailing_empty_line() -> Result<(), ShellError> {
+        let value = string("andres\n\njonathan\n");
+
+        assert_eq!(
+            value.lines()?,
+            table(&[line("andres"), line(""), line("jonathan")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn changes_case_of_ascii() -> Result<(), ShellError> {
+        assert_eq!(string("NuShell").str_to_upper()?, string("NUSHELL"));
+        assert_eq!(string("NuShell").str_to_lower()?, string("nushell"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn changes_case_of_dotted_i() -> Result<(), ShellError> {
+        // Case mapping is locale independent, so the Turkish dotted capital I lowercases to
+        // an `i` followed by a combining dot above rather than to a plain `i`
+        assert_eq!(string("İ").str_to_lower()?, string("i\u{307}"));
+        assert_eq!(string("i").str_to_upper()?, string("I"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn capitalizes_multiple_words() -> Result<(), ShellError> {
+        let value = string("hello  nu shELL");
+
+        assert_eq!(value.str_capitalize()?, string("Hello  nu shell"));
+        assert_eq!(value.str_title_case()?, string("Hello  Nu Shell"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn capitalizes_already_capitalized_input() -> Result<(), ShellError> {
+        let value = string("Nu Shell");
+
+        assert_eq!(value.str_capitalize()?, string("Nu shell"));
+        assert_eq!(value.str_title_case()?, string("Nu Shell"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pads_on_the_left() -> Result<(), ShellError> {
+        assert_eq!(
+            string("42").str_pad(5, '0', PadSide::Left)?,
+            string("00042")
+        );
+        assert_eq!(string("ü").str_pad(3, '*', PadSide::Left)?, string("**ü"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pads_on_the_right() -> Result<(), ShellError> {
+        assert_eq!(
+            string("nu").str_pad(5, '.', PadSide::Right)?,
+            string("nu...")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pad_leaves_wide_strings_untouched() -> Result<(), ShellError> {
+        assert_eq!(
+            string("nushell").str_pad(4, ' ', PadSide::Left)?,
+            string("nushell")
+        );
+        assert_eq!(
+            string("nushell").str_pad(7, ' ', PadSide::Right)?,
+            string("nushell")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reverses_ascii() -> Result<(), ShellError> {
+        assert_eq!(string("nushell").str_reverse()?, string("llehsun"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reverse_keeps_combining_characters_attached() -> Result<(), ShellError> {
+        assert_eq!(
+            string("cafe\u{301}s").str_reverse()?,
+            string("se\u{301}fac")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_decimal_integers() -> Result<(), ShellError> {
+        assert_eq!(string("42").parse_int(10)?, int(42));
+        assert_eq!(string(" -42 ").parse_int(0)?, int(-42));
+        assert_eq!(string("101").parse_int(2)?, int(5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_hex_integers() -> Result<(), ShellError> {
+        assert_eq!(string("ff").parse_int(16)?, int(255));
+        assert_eq!(string("0xFF").parse_int(0)?, int(255));
+        assert_eq!(string("-0x10").parse_int(0)?, int(-16));
+        assert_eq!(string("0b110").parse_int(0)?, int(6));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_floats() -> Result<(), ShellError> {
+        assert_eq!(
+            string("1.5").parse_float()?,
+            UntaggedValue::decimal(BigDecimal::from_str("1.5").unwrap()).into_untagged_value()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors_on_invalid_strings() {
+        assert!(string("nu").parse_int(10).is_err());
+        assert!(string("0x").parse_int(0).is_err());
+        assert!(string("12").parse_int(1).is_err());
+        assert!(string("--5").parse_int(10).is_err());
+        assert!(string("-+5").parse_int(10).is_err());
+        assert!(string("+-5").parse_int(0).is_err());
+        assert!(string("0x-5").parse_int(0).is_err());
+        assert!(string("1.2.3").parse_float().is_err());
+    }
+
+    #[test]
+    fn contains_errors_on_non_strings() {
+        let value = UntaggedValue::int(1).into_untagged_value();
+
+        assert!(value.str_contains("1", false).is_err());
+        assert!(value.str_to_upper().is_err());
+        assert!(value.str_capitalize().is_err());
+        assert!(value.str_reverse().is_err());
+    }
+}
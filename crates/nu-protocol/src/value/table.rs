@@ -0,0 +1,1911 @@
+use crate::type_name::{ShellTypeName, SpannedTypeName};
+use crate::value::dict::{Dictionary, TaggedDictBuilder};
+use crate::value::primitive::{format_primitive, Primitive};
+use crate::value::range::Range;
+use crate::value::{merge_descriptors, UntaggedValue, Value};
+use indexmap::IndexMap;
+use nu_errors::ShellError;
+use nu_source::{SpannedItem, Tag};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::atomic::{self, AtomicUsize};
+
+impl UntaggedValue {
+    /// Merge tables by taking one row from each table in turn. Tables that run out of rows are skipped.
+    pub fn interleave(tables: &[&[Value]]) -> UntaggedValue {
+        let mut sources: Vec<_> = tables.iter().map(|table| table.iter()).collect();
+        let mut rows = Vec::with_capacity(tables.iter().map(|table| table.len()).sum());
+
+        while !sources.is_empty() {
+            sources.retain(|source| !source.as_slice().is_empty());
+
+            for source in sources.iter_mut() {
+                if let Some(row) = source.next() {
+                    rows.push(row.clone());
+                }
+            }
+        }
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Split a table into the rows that match the predicate and the rows that don't, keeping the order of each
+    pub fn partition(
+        table: &[Value],
+        pred: impl Fn(&Value) -> bool,
+    ) -> (UntaggedValue, UntaggedValue) {
+        let (matching, rest): (Vec<Value>, Vec<Value>) =
+            table.iter().cloned().partition(|row| pred(row));
+
+        (UntaggedValue::Table(matching), UntaggedValue::Table(rest))
+    }
+
+    /// Find the index of the first row that matches the predicate
+    pub fn find_index(table: &[Value], pred: impl Fn(&Value) -> bool) -> Option<usize> {
+        table.iter().position(|row| pred(row))
+    }
+
+    /// Find the distinct rows present in both tables, in the order they are first seen in `a`
+    pub fn intersect(a: &[Value], b: &[Value]) -> UntaggedValue {
+        let in_b: HashSet<Value> = b.iter().map(without_tags).collect();
+        let mut seen = HashSet::new();
+        let mut rows: Vec<Value> = vec![];
+
+        for row in a {
+            let key = without_tags(row);
+
+            if in_b.contains(&key) && seen.insert(key) {
+                rows.push(row.clone());
+            }
+        }
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Find the distinct rows present in either table, in the order they are first seen in `a` then `b`
+    pub fn union(a: &[Value], b: &[Value]) -> UntaggedValue {
+        let mut seen = HashSet::new();
+        let mut rows: Vec<Value> = vec![];
+
+        for row in a.iter().chain(b.iter()) {
+            if seen.insert(without_tags(row)) {
+                rows.push(row.clone());
+            }
+        }
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Find the rows of `a` that aren't present in `b`, keeping the order of `a`
+    pub fn difference(a: &[Value], b: &[Value]) -> UntaggedValue {
+        let in_b: HashSet<Value> = b.iter().map(without_tags).collect();
+
+        UntaggedValue::Table(
+            a.iter()
+                .filter(|row| !in_b.contains(&without_tags(row)))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Flatten a column of inner tables, giving one row per element of the inner table. Inner rows have their
+    /// fields merged into the outer row in place of the column. Rows where the column isn't a table are kept as-is,
+    /// and rows where it is an empty table are kept with the column set to Nothing.
+    pub fn flatten_column(table: &[Value], column: &str) -> Result<UntaggedValue, ShellError> {
+        let mut rows = vec![];
+
+        for row in table {
+            let dict = match &row.value {
+                UntaggedValue::Row(dict) => dict,
+                _ => return Err(ShellError::type_error("row", row.spanned_type_name())),
+            };
+
+            let inner = match dict.entries.get(column) {
+                Some(Value {
+                    value: UntaggedValue::Table(inner),
+                    ..
+                }) if inner.is_empty() => {
+                    let mut entries = dict.entries.clone();
+                    entries.insert(
+                        column.to_string(),
+                        UntaggedValue::nothing().into_value(&row.tag),
+                    );
+
+                    rows.push(UntaggedValue::row(entries).into_value(&row.tag));
+                    continue;
+                }
+                Some(Value {
+                    value: UntaggedValue::Table(inner),
+                    ..
+                }) => inner,
+                _ => {
+                    rows.push(row.clone());
+                    continue;
+                }
+            };
+
+            for element in inner {
+                let mut entries = dict.entries.clone();
+                entries.shift_remove(column);
+
+                match &element.value {
+                    UntaggedValue::Row(inner_dict) => {
+                        for (key, value) in inner_dict.entries.iter() {
+                            entries.insert(key.clone(), value.clone());
+                        }
+                    }
+                    _ => {
+                        entries.insert(column.to_string(), element.clone());
+                    }
+                }
+
+                rows.push(UntaggedValue::row(entries).into_value(&row.tag));
+            }
+        }
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Reshape a table from long to wide format. The result has one row per distinct value of the `index` column,
+    /// with a column for each distinct value of the `columns` column holding the matching value of the `values`
+    /// column. If several rows give a value for the same cell, the last one wins. Missing cells are Nothing.
+    pub fn pivot(
+        table: &[Value],
+        index: &str,
+        columns: &str,
+        values: &str,
+    ) -> Result<UntaggedValue, ShellError> {
+        let mut indices: Vec<&Value> = vec![];
+        let mut names: Vec<String> = vec![];
+        let mut cells: IndexMap<(usize, String), &Value> = IndexMap::new();
+
+        for row in table {
+            let index_value = column_of(row, index)?;
+            let name = column_name(column_of(row, columns)?)?;
+            let value = column_of(row, values)?;
+
+            let position = match indices
+                .iter()
+                .position(|i| i.equals_ignoring_tags(index_value))
+            {
+                Some(position) => position,
+                None => {
+                    indices.push(index_value);
+                    indices.len() - 1
+                }
+            };
+
+            if !names.contains(&name) {
+                names.push(name.clone());
+            }
+
+            cells.insert((position, name), value);
+        }
+
+        let rows = indices
+            .into_iter()
+            .enumerate()
+            .map(|(position, index_value)| {
+                let mut row = TaggedDictBuilder::new(&index_value.tag);
+                row.insert_value(index, index_value.clone());
+
+                for name in names.iter() {
+                    match cells.get(&(position, name.clone())) {
+                        Some(value) => row.insert_value(name.clone(), (*value).clone()),
+                        None => row.insert_untagged(name.clone(), UntaggedValue::nothing()),
+                    }
+                }
+
+                row.into_value()
+            })
+            .collect();
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Split a string column on the separator, giving one row per piece with the other columns duplicated
+    pub fn explode(
+        table: &[Value],
+        column: &str,
+        separator: &str,
+    ) -> Result<UntaggedValue, ShellError> {
+        let mut rows = vec![];
+
+        for row in table {
+            let value = column_of(row, column)?;
+            let string = value.as_forgiving_string()?;
+
+            if let UntaggedValue::Row(dict) = &row.value {
+                for piece in string.split(separator) {
+                    let mut entries = dict.entries.clone();
+                    entries.insert(
+                        column.to_string(),
+                        UntaggedValue::string(piece).into_value(&value.tag),
+                    );
+
+                    rows.push(UntaggedValue::row(entries).into_value(&row.tag));
+                }
+            }
+        }
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Add a column `into` to the row holding the first value of the listed columns that isn't Nothing. If every
+    /// listed column is Nothing or missing, the new column is Nothing.
+    pub fn coalesce_columns(row: &Dictionary, columns: &[String], into: &str) -> Dictionary {
+        let value = columns
+            .iter()
+            .filter_map(|column| row.entries.get(column))
+            .find(|value| value.is_some())
+            .cloned()
+            .unwrap_or_else(|| UntaggedValue::nothing().into_untagged_value());
+
+        let mut coalesced = row.clone();
+        coalesced.insert_data_at_key(into, value);
+        coalesced
+    }
+
+    /// Thread an accumulator through the rows of a table, giving a table of the accumulator after each row.
+    /// `f` is called with the current accumulator and the row and returns the next accumulator.
+    pub fn scan(
+        table: &[Value],
+        init: Value,
+        mut f: impl FnMut(&Value, &Value) -> Result<Value, ShellError>,
+    ) -> Result<UntaggedValue, ShellError> {
+        let mut states: Vec<Value> = Vec::with_capacity(table.len());
+        let mut acc = init;
+
+        for row in table {
+            acc = f(&acc, row)?;
+            states.push(acc.clone());
+        }
+
+        Ok(UntaggedValue::Table(states))
+    }
+
+    /// Fold the values into a single value, starting from `init`. `f` takes ownership of the accumulator so it can
+    /// be updated without cloning. The first error stops the fold.
+    pub fn reduce(
+        values: &[Value],
+        init: Value,
+        f: impl FnMut(Value, &Value) -> Result<Value, ShellError>,
+    ) -> Result<Value, ShellError> {
+        values.iter().try_fold(init, f)
+    }
+
+    /// Replace Nothing cells in a column with the closest value above them that isn't Nothing. Nothing cells
+    /// before the first value are left alone.
+    pub fn fill_down(table: &[Value], column: &str) -> Result<UntaggedValue, ShellError> {
+        let mut rows = Vec::with_capacity(table.len());
+        let mut last: Option<&Value> = None;
+
+        for row in table {
+            let cell = column_of(row, column)?;
+
+            match (cell.is_some(), last) {
+                (true, _) => {
+                    last = Some(cell);
+                    rows.push(row.clone());
+                }
+                (false, Some(value)) => {
+                    let mut filled = row.clone();
+                    if let UntaggedValue::Row(dict) = &mut filled.value {
+                        dict.insert_data_at_key(column, value.clone());
+                    }
+                    rows.push(filled);
+                }
+                (false, None) => rows.push(row.clone()),
+            }
+        }
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Add a 1-based `rank` column to each row, ranking rows by the value of `column`. Rows with equal values
+    /// share the lowest rank among them, and the following rank is skipped. Rows keep their original order.
+    pub fn rank(
+        table: &[Value],
+        column: &str,
+        descending: bool,
+    ) -> Result<UntaggedValue, ShellError> {
+        let keys = table
+            .iter()
+            .map(|row| column_of(row, column))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut error = None;
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+
+        order.sort_by(|left, right| {
+            let ordering = compare_values(keys[*left], keys[*right]).unwrap_or_else(|err| {
+                error.get_or_insert(err);
+                Ordering::Equal
+            });
+
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        if let Some(err) = error {
+            return Err(err);
+        }
+
+        let mut ranks = vec![0; keys.len()];
+
+        for (position, index) in order.iter().enumerate() {
+            ranks[*index] = match position {
+                0 => 1,
+                _ => {
+                    let previous = order[position - 1];
+
+                    if compare_values(keys[previous], keys[*index])? == Ordering::Equal {
+                        ranks[previous]
+                    } else {
+                        position + 1
+                    }
+                }
+            };
+        }
+
+        let rows = table
+            .iter()
+            .zip(ranks)
+            .map(|(row, rank)| {
+                let mut ranked = row.clone();
+                if let UntaggedValue::Row(dict) = &mut ranked.value {
+                    dict.insert_data_at_key("rank", UntaggedValue::int(rank).into_value(&row.tag));
+                }
+                ranked
+            })
+            .collect();
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Pick `n` rows at random, without replacement. If `n` is at least the number of rows, all of the rows are
+    /// returned in random order. Passing a seed makes the choice reproducible.
+    pub fn sample(table: &[Value], n: usize, seed: Option<u64>) -> UntaggedValue {
+        let mut rng = rng_from(seed);
+
+        let mut rows: Vec<Value> = if n >= table.len() {
+            table.to_vec()
+        } else {
+            table.choose_multiple(&mut rng, n).cloned().collect()
+        };
+        rows.shuffle(&mut rng);
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Put the rows of a table in random order. Passing a seed makes the order reproducible.
+    pub fn shuffle(table: &[Value], seed: Option<u64>) -> UntaggedValue {
+        let mut rows = table.to_vec();
+        rows.shuffle(&mut rng_from(seed));
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Rotate the rows of a table, wrapping around. A positive amount moves rows down, so the last row becomes
+    /// the first, and a negative amount moves rows up.
+    pub fn roll_rows(table: &[Value], by: i64) -> UntaggedValue {
+        let mut rows = table.to_vec();
+        let shift = rotation(by, rows.len());
+        rows.rotate_right(shift);
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Rotate the columns of a row, or of each row in a table, wrapping around. A positive amount moves
+    /// columns right, so the last column becomes the first, and a negative amount moves columns left.
+    pub fn roll_columns(row_or_table: &Value, by: i64) -> Result<Value, ShellError> {
+        match &row_or_table.value {
+            UntaggedValue::Row(dict) => {
+                let mut entries: Vec<(String, Value)> = dict
+                    .entries
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                let shift = rotation(by, entries.len());
+                entries.rotate_right(shift);
+
+                Ok(UntaggedValue::row(entries.into_iter().collect()).into_value(&row_or_table.tag))
+            }
+            UntaggedValue::Table(table) => {
+                let rows = table
+                    .iter()
+                    .map(|row| UntaggedValue::roll_columns(row, by))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(UntaggedValue::Table(rows).into_value(&row_or_table.tag))
+            }
+            _ => Err(ShellError::type_error(
+                "row",
+                row_or_table.spanned_type_name(),
+            )),
+        }
+    }
+
+    /// Summarize the types found in each column of a table, giving a row per column with the `column` name and
+    /// the distinct `types` seen in it, in the order they were first seen. Values that aren't rows are counted
+    /// under a `<value>` column.
+    pub fn column_type_summary(table: &[Value]) -> UntaggedValue {
+        let mut columns: IndexMap<String, Vec<&'static str>> = IndexMap::new();
+
+        for row in table {
+            let cells: Vec<(&str, &Value)> = match &row.value {
+                UntaggedValue::Row(dict) => dict
+                    .entries
+                    .iter()
+                    .map(|(key, value)| (&key[..], value))
+                    .collect(),
+                _ => vec![("<value>", row)],
+            };
+
+            for (column, value) in cells {
+                let types = columns.entry(column.to_string()).or_insert_with(Vec::new);
+                let type_name = value.type_name();
+
+                if !types.contains(&type_name) {
+                    types.push(type_name);
+                }
+            }
+        }
+
+        let rows = columns
+            .into_iter()
+            .map(|(column, types)| {
+                let types: Vec<Value> = types
+                    .into_iter()
+                    .map(|type_name| UntaggedValue::string(type_name).into_untagged_value())
+                    .collect();
+
+                let mut row = TaggedDictBuilder::new(Tag::unknown());
+                row.insert_untagged("column", UntaggedValue::string(column));
+                row.insert_untagged("types", UntaggedValue::Table(types));
+                row.into_value()
+            })
+            .collect();
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Coerce every cell of a column to `target_type`, one of "integer", "decimal", "string", "filesize",
+    /// "date" or "binary", using the matching `into_*` coercion. Every cell is tried, and all of the
+    /// failures are reported together in one error.
+    pub fn coerce_column(
+        table: &[Value],
+        column: &str,
+        target_type: &str,
+    ) -> Result<UntaggedValue, ShellError> {
+        let coerce: fn(&Value) -> Result<Value, ShellError> = match target_type {
+            "int" | "integer" => Value::into_int,
+            "decimal" => Value::into_decimal,
+            "string" => |value| value.into_string_value(None),
+            "filesize" => Value::into_filesize,
+            "date" => |value| value.into_datetime(None),
+            "binary" => Value::into_binary,
+            other => {
+                return Err(ShellError::untagged_runtime_error(format!(
+                    "Cannot coerce a column to '{}'",
+                    other
+                )))
+            }
+        };
+
+        let mut rows = Vec::with_capacity(table.len());
+        let mut failures = vec![];
+        let mut failure_tag = None;
+
+        for (index, row) in table.iter().enumerate() {
+            match column_of(row, column).and_then(coerce) {
+                Ok(coerced) => {
+                    let mut updated = row.clone();
+                    if let UntaggedValue::Row(dict) = &mut updated.value {
+                        dict.insert_data_at_key(column, coerced);
+                    }
+                    rows.push(updated);
+                }
+                Err(err) => {
+                    failure_tag.get_or_insert_with(|| row.tag.clone());
+                    failures.push(format!("row {}: {}", index, describe_error(err)));
+                }
+            }
+        }
+
+        match failure_tag {
+            None => Ok(UntaggedValue::Table(rows)),
+            Some(tag) => Err(ShellError::labeled_error(
+                format!("Could not coerce column '{}' to {}", column, target_type),
+                failures.join("; "),
+                tag,
+            )),
+        }
+    }
+
+    /// Count the Nothing or missing cells of each column, giving a single row with a count per column. Values
+    /// that aren't rows are counted under a `<value>` column.
+    pub fn null_counts(table: &[Value]) -> UntaggedValue {
+        let mut counts = TaggedDictBuilder::new(Tag::unknown());
+
+        for column in merge_descriptors(table) {
+            let count = table
+                .iter()
+                .filter(|row| match &row.value {
+                    UntaggedValue::Row(dict) => match dict.entries.get(&column) {
+                        Some(value) => value.is_none(),
+                        None => true,
+                    },
+                    _ => column != "<value>" || row.is_none(),
+                })
+                .count();
+
+            counts.insert_untagged(column, UntaggedValue::int(count));
+        }
+
+        counts.into_untagged_value()
+    }
+
+    /// Add a column to every row, holding the value computed by `f` from that row. If `f` fails, the error points
+    /// at the row it failed on.
+    pub fn insert_column(
+        table: &[Value],
+        name: &str,
+        f: impl Fn(&Value) -> Result<Value, ShellError>,
+    ) -> Result<UntaggedValue, ShellError> {
+        let mut rows = Vec::with_capacity(table.len());
+
+        for row in table {
+            let mut dict = match &row.value {
+                UntaggedValue::Row(dict) => dict.clone(),
+                _ => return Err(ShellError::type_error("row", row.spanned_type_name())),
+            };
+
+            let value = f(row).map_err(|err| {
+                ShellError::labeled_error(
+                    format!("Could not compute column '{}'", name),
+                    describe_error(err),
+                    &row.tag,
+                )
+            })?;
+
+            dict.insert_data_at_key(name, value);
+            rows.push(UntaggedValue::Row(dict).into_value(&row.tag));
+        }
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Remove the rows at the given indices, keeping the rest in order. Indices past the end are ignored.
+    pub fn drop_nth(table: &[Value], indices: &[usize]) -> UntaggedValue {
+        let rows = table
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !indices.contains(index))
+            .map(|(_, row)| row.clone())
+            .collect();
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Keep every `stride`-th row, starting with the first. With `skip`, those rows are dropped and the rest
+    /// are kept instead. A stride of zero is an error.
+    pub fn every_nth(
+        table: &[Value],
+        stride: usize,
+        skip: bool,
+    ) -> Result<UntaggedValue, ShellError> {
+        if stride == 0 {
+            return Err(ShellError::untagged_runtime_error(
+                "Cannot take every 0th row, the stride must be at least 1",
+            ));
+        }
+
+        let rows = table
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| (index % stride == 0) != skip)
+            .map(|(_, row)| row.clone())
+            .collect();
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Replace each cell of a column with the result of `f`. Every row must have the column.
+    pub fn update_column(
+        table: &[Value],
+        column: &str,
+        f: impl Fn(&Value) -> Result<Value, ShellError>,
+    ) -> Result<UntaggedValue, ShellError> {
+        let mut rows = Vec::with_capacity(table.len());
+
+        for row in table {
+            let value = f(column_of(row, column)?)?;
+
+            let mut dict = match &row.value {
+                UntaggedValue::Row(dict) => dict.clone(),
+                _ => return Err(ShellError::type_error("row", row.spanned_type_name())),
+            };
+
+            dict.insert_data_at_key(column, value);
+            rows.push(UntaggedValue::Row(dict).into_value(&row.tag));
+        }
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Make sure every row has a column, filling in `default` where it is missing or empty.
+    pub fn default_column(table: &[Value], column: &str, default: Value) -> UntaggedValue {
+        let rows = table
+            .iter()
+            .map(|row| match &row.value {
+                UntaggedValue::Row(dict) => match dict.entries.get(column) {
+                    Some(value) if !value.is_none() => row.clone(),
+                    _ => {
+                        let mut dict = dict.clone();
+                        dict.insert_data_at_key(column, default.clone());
+                        UntaggedValue::Row(dict).into_value(&row.tag)
+                    }
+                },
+                _ => row.clone(),
+            })
+            .collect();
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Drop rows missing any of the `required` columns, or rows with no values at all when none are required.
+    pub fn compact_rows(table: &[Value], required: &[String]) -> UntaggedValue {
+        let rows = table
+            .iter()
+            .filter(|row| match &row.value {
+                UntaggedValue::Row(dict) if required.is_empty() => {
+                    dict.entries.values().any(|value| value.is_some())
+                }
+                UntaggedValue::Row(dict) => required.iter().all(|column| {
+                    dict.entries
+                        .get(column)
+                        .map(|value| value.is_some())
+                        .unwrap_or(false)
+                }),
+                _ => required.is_empty() && row.is_some(),
+            })
+            .cloned()
+            .collect();
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Take rows from the start of a table for as long as they satisfy the predicate
+    pub fn take_while(table: &[Value], pred: impl Fn(&Value) -> bool) -> UntaggedValue {
+        UntaggedValue::Table(table.iter().take_while(|row| pred(row)).cloned().collect())
+    }
+
+    /// Take rows from the start of a table until one satisfies the predicate
+    pub fn take_until(table: &[Value], pred: impl Fn(&Value) -> bool) -> UntaggedValue {
+        UntaggedValue::Table(table.iter().take_while(|row| !pred(row)).cloned().collect())
+    }
+
+    /// Find the rows where any cell, converted to a string, contains the needle
+    pub fn find(table: &[Value], needle: &str, insensitive: bool) -> UntaggedValue {
+        let needle = if insensitive {
+            needle.to_lowercase()
+        } else {
+            needle.to_string()
+        };
+
+        let matches = |cell: &Value| {
+            let text = cell.as_lossy_string();
+
+            if insensitive {
+                text.to_lowercase().contains(&needle)
+            } else {
+                text.contains(&needle)
+            }
+        };
+
+        let rows = table
+            .iter()
+            .filter(|row| match &row.value {
+                UntaggedValue::Row(dict) => dict.entries.values().any(&matches),
+                _ => matches(row),
+            })
+            .cloned()
+            .collect();
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Keep the rows where a column is equal to the given value. Cells that can't be compared with the
+    /// value don't match, but every row must have the column.
+    pub fn filter_eq(
+        table: &[Value],
+        column: &str,
+        value: &Value,
+    ) -> Result<UntaggedValue, ShellError> {
+        let mut rows = vec![];
+
+        for row in table {
+            let cell = column_of(row, column)?;
+
+            if let Ok(Ordering::Equal) = compare_values(cell, value) {
+                rows.push(row.clone());
+            }
+        }
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Keep the rows where a numeric column falls within the range. Rows with a non-numeric value in the column
+    /// are skipped, but every row must have the column.
+    pub fn filter_range(
+        table: &[Value],
+        column: &str,
+        range: &Range,
+    ) -> Result<UntaggedValue, ShellError> {
+        let mut rows = vec![];
+
+        for row in table {
+            if let UntaggedValue::Primitive(primitive) = &column_of(row, column)?.value {
+                let numeric = match primitive {
+                    Primitive::Int(_) | Primitive::Decimal(_) => true,
+                    _ => false,
+                };
+
+                if numeric && range.contains(primitive) {
+                    rows.push(row.clone());
+                }
+            }
+        }
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Apply `f` to every row of a table in parallel, keeping the rows in their original order. If `f` fails,
+    /// rows after the failing one are skipped and the error from the earliest failing row is returned.
+    pub fn par_map(
+        table: &[Value],
+        f: impl Fn(&Value) -> Result<Value, ShellError> + Sync,
+    ) -> Result<UntaggedValue, ShellError> {
+        let first_error = AtomicUsize::new(usize::max_value());
+
+        let results: Vec<Option<Result<Value, ShellError>>> = table
+            .par_iter()
+            .enumerate()
+            .map(|(index, row)| {
+                if index > first_error.load(atomic::Ordering::SeqCst) {
+                    return None;
+                }
+
+                let result = f(row);
+                if result.is_err() {
+                    lower_to(&first_error, index);
+                }
+
+                Some(result)
+            })
+            .collect();
+
+        let rows = results
+            .into_iter()
+            .flatten()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(UntaggedValue::Table(rows))
+    }
+
+    /// Add items to the front of a table. Input that isn't a table is treated as a table of one row.
+    pub fn prepend(input: &Value, items: &[Value]) -> UntaggedValue {
+        let mut rows = items.to_vec();
+        rows.extend(rows_of(input));
+
+        UntaggedValue::Table(rows)
+    }
+
+    /// Add items to the end of a table. Input that isn't a table is treated as a table of one row.
+    pub fn append(input: &Value, items: &[Value]) -> UntaggedValue {
+        let mut rows = rows_of(input);
+        rows.extend_from_slice(items);
+
+        UntaggedValue::Table(rows)
+    }
+}
+
+/// Get the rows of a table, or a single row holding any other value
+fn rows_of(value: &Value) -> Vec<Value> {
+    match &value.value {
+        UntaggedValue::Table(rows) => rows.clone(),
+        _ => vec![value.clone()],
+    }
+}
+
+/// Lower an atomic index to the given value, if it is currently higher
+fn lower_to(atomic: &AtomicUsize, index: usize) {
+    let mut current = atomic.load(atomic::Ordering::SeqCst);
+
+    while index < current {
+        match atomic.compare_exchange(
+            current,
+            index,
+            atomic::Ordering::SeqCst,
+            atomic::Ordering::SeqCst,
+        ) {
+            Ok(_) => break,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+/// Describe an error in a single line, from its message and primary label
+fn describe_error(err: ShellError) -> String {
+    let diagnostic = err.into_diagnostic();
+
+    match diagnostic
+        .labels
+        .first()
+        .and_then(|label| label.message.clone())
+    {
+        Some(label) => format!("{} ({})", diagnostic.message, label),
+        None => diagnostic.message,
+    }
+}
+
+/// Turn a signed rotation into the equivalent number of places to rotate right
+fn rotation(by: i64, len: usize) -> usize {
+    if len == 0 {
+        0
+    } else {
+        by.rem_euclid(len as i64) as usize
+    }
+}
+
+/// Create a random number generator, seeded if a seed is given and from system entropy otherwise
+fn rng_from(seed: Option<u64>) -> ChaCha8Rng {
+    match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_entropy(),
+    }
+}
+
+/// Get a primitive value as a column name
+fn column_name(value: &Value) -> Result<String, ShellError> {
+    match &value.value {
+        UntaggedValue::Primitive(primitive) => match primitive.text() {
+            Some(text) => Ok(text.to_string()),
+            None => Ok(format_primitive(primitive, None)),
+        },
+        _ => Err(ShellError::type_error(
+            "primitive",
+            value.spanned_type_name(),
+        )),
+    }
+}
+
+/// Copy a Value with its tag, and the tags of any Values nested inside it, cleared. Two Values that are
+/// `equals_ignoring_tags` give equal copies, so the copies can be hashed to compare rows.
+fn without_tags(value: &Value) -> Value {
+    let untagged = match &value.value {
+        UntaggedValue::Row(dict) => UntaggedValue::row(
+            dict.entries()
+                .iter()
+                .map(|(key, value)| (key.clone(), without_tags(value)))
+                .collect::<IndexMap<_, _>>(),
+        ),
+        UntaggedValue::Table(table) => {
+            UntaggedValue::Table(table.iter().map(without_tags).collect())
+        }
+        other => other.clone(),
+    };
+
+    untagged.into_untagged_value()
+}
+
+/// Get the value of the named column of a row, erroring if the value isn't a row or lacks the column
+pub(crate) fn column_of<'a>(row: &'a Value, column: &str) -> Result<&'a Value, ShellError> {
+    match &row.value {
+        UntaggedValue::Row(dict) => dict.entries.get(column).ok_or_else(|| {
+            ShellError::labeled_error(
+                "Unknown column",
+                format!("row does not contain a column named '{}'", column),
+                &row.tag,
+            )
+        }),
+        _ => Err(ShellError::type_error("row", row.spanned_type_name())),
+    }
+}
+
+/// Compare two primitive values, erroring if they aren't primitives or can't be compared with each other
+pub(crate) fn compare_values(left: &Value, right: &Value) -> Result<Ordering, ShellError> {
+    match (&left.value, &right.value) {
+        (UntaggedValue::Primitive(left_primitive), UntaggedValue::Primitive(right_primitive)) => {
+            left_primitive
+                .compare(right_primitive)
+                .map_err(|(left_type, right_type)| {
+                    ShellError::coerce_error(
+                        left_type.spanned(left.tag.span),
+                        right_type.spanned(right.tag.span),
+                    )
+                })
+        }
+        _ => Err(ShellError::coerce_error(
+            left.spanned_type_name(),
+            right.spanned_type_name(),
+        )),
+    }
+}
+
+/// Sort values using primitive comparison, erroring if any two values can't be compared
+pub(crate) fn sort_values(values: &[Value]) -> Result<Vec<&Value>, ShellError> {
+    let mut error = None;
+    let mut sorted: Vec<&Value> = values.iter().collect();
+
+    sorted.sort_by(|left, right| {
+        compare_values(left, right).unwrap_or_else(|err| {
+            error.get_or_insert(err);
+            Ordering::Equal
+        })
+    });
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(sorted),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{int, nothing, row, string, table};
+    use crate::{Primitive, Range, RangeInclusion, UntaggedValue, Value};
+    use nu_errors::ShellError;
+    use nu_source::{Span, SpannedItem};
+    use num_bigint::BigInt;
+
+    fn ints(input: &[i64]) -> Vec<Value> {
+        input.iter().map(|i| int(*i)).collect()
+    }
+
+    #[test]
+    fn interleaves_tables_of_unequal_length() {
+        let left = ints(&[1, 3, 5, 6, 7]);
+        let right = ints(&[2, 4]);
+
+        assert_eq!(
+            UntaggedValue::interleave(&[&left[..], &right[..]]),
+            UntaggedValue::Table(ints(&[1, 2, 3, 4, 5, 6, 7]))
+        );
+        assert_eq!(UntaggedValue::interleave(&[]), UntaggedValue::Table(vec![]));
+    }
+
+    fn is_even(value: &Value) -> bool {
+        match &value.value {
+            UntaggedValue::Primitive(Primitive::Int(int)) => {
+                int % BigInt::from(2) == BigInt::from(0)
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn intersect_and_union_of_overlapping_tables() {
+        let a = ints(&[1, 2, 3, 2]);
+        let b = ints(&[4, 3, 2]);
+
+        assert_eq!(
+            UntaggedValue::intersect(&a, &b),
+            UntaggedValue::Table(ints(&[2, 3]))
+        );
+        assert_eq!(
+            UntaggedValue::union(&a, &b),
+            UntaggedValue::Table(ints(&[1, 2, 3, 4]))
+        );
+    }
+
+    #[test]
+    fn intersect_and_union_of_disjoint_tables() {
+        let a = ints(&[1, 2]);
+        let b = ints(&[3, 4]);
+
+        assert_eq!(
+            UntaggedValue::intersect(&a, &b),
+            UntaggedValue::Table(vec![])
+        );
+        assert_eq!(
+            UntaggedValue::union(&a, &b),
+            UntaggedValue::Table(ints(&[1, 2, 3, 4]))
+        );
+    }
+
+    #[test]
+    fn intersect_and_union_ignore_tags_and_column_order() {
+        let tagged =
+            |value: Value, start: usize| value.value.into_value(Span::new(start, start + 1));
+
+        let a = vec![
+            tagged(row(vec![("name", string("nu")), ("age", int(1))]), 0),
+            row(vec![("name", string("bash")), ("age", int(30))]),
+        ];
+        let b = vec![tagged(
+            row(vec![("age", tagged(int(1), 7)), ("name", string("nu"))]),
+            5,
+        )];
+
+        assert_eq!(
+            UntaggedValue::intersect(&a, &b),
+            UntaggedValue::Table(vec![a[0].clone()])
+        );
+        assert_eq!(UntaggedValue::union(&a, &b), UntaggedValue::Table(a));
+    }
+
+    #[test]
+    fn difference_with_partial_overlap() {
+        let a = ints(&[1, 2, 3, 4, 2]);
+        let b = ints(&[2, 4]);
+
+        assert_eq!(
+            UntaggedValue::difference(&a, &b),
+            UntaggedValue::Table(ints(&[1, 3]))
+        );
+    }
+
+    #[test]
+    fn difference_ignores_tags() {
+        let a = vec![int(1), int(2).value.into_value(Span::new(3, 4))];
+        let b = vec![int(2).value.into_value(Span::new(8, 9))];
+
+        assert_eq!(
+            UntaggedValue::difference(&a, &b),
+            UntaggedValue::Table(ints(&[1]))
+        );
+    }
+
+    #[test]
+    fn difference_with_full_subtraction() {
+        let a = ints(&[1, 2]);
+        let b = ints(&[2, 1, 3]);
+
+        assert_eq!(
+            UntaggedValue::difference(&a, &b),
+            UntaggedValue::Table(vec![])
+        );
+    }
+
+    #[test]
+    fn flattens_nested_tables_of_differing_lengths() -> Result<(), ShellError> {
+        let table = vec![
+            row(vec![
+                ("name", string("nu")),
+                (
+                    "authors",
+                    table(&[
+                        row(vec![("author", string("andres"))]),
+                        row(vec![("author", string("jonathan"))]),
+                    ]),
+                ),
+            ]),
+            row(vec![
+                ("name", string("nu-cli")),
+                ("authors", table(&[row(vec![("author", string("yehuda"))])])),
+            ]),
+            row(vec![
+                ("name", string("nu-source")),
+                ("authors", string("unknown")),
+            ]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::flatten_column(&table, "authors")?,
+            UntaggedValue::Table(vec![
+                row(vec![("name", string("nu")), ("author", string("andres"))]),
+                row(vec![("name", string("nu")), ("author", string("jonathan"))]),
+                row(vec![
+                    ("name", string("nu-cli")),
+                    ("author", string("yehuda"))
+                ]),
+                row(vec![
+                    ("name", string("nu-source")),
+                    ("authors", string("unknown"))
+                ]),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn flatten_keeps_rows_with_empty_tables() -> Result<(), ShellError> {
+        let table = vec![
+            row(vec![
+                ("name", string("nu")),
+                ("authors", table(&[row(vec![("author", string("andres"))])])),
+            ]),
+            row(vec![("name", string("nu-source")), ("authors", table(&[]))]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::flatten_column(&table, "authors")?,
+            UntaggedValue::Table(vec![
+                row(vec![("name", string("nu")), ("author", string("andres"))]),
+                row(vec![
+                    ("name", string("nu-source")),
+                    ("authors", UntaggedValue::nothing().into_untagged_value())
+                ]),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pivots_long_table_to_wide() -> Result<(), ShellError> {
+        let sales = |year: i64, quarter: &str, amount: i64| {
+            row(vec![
+                ("year", int(year)),
+                ("quarter", string(quarter)),
+                ("amount", int(amount)),
+            ])
+        };
+
+        let table = vec![
+            sales(2019, "q1", 10),
+            sales(2019, "q2", 20),
+            sales(2020, "q1", 30),
+            sales(2019, "q2", 25),
+            sales(2020, "q3", 40),
+        ];
+
+        let nothing = UntaggedValue::nothing().into_untagged_value();
+
+        assert_eq!(
+            UntaggedValue::pivot(&table, "year", "quarter", "amount")?,
+            UntaggedValue::Table(vec![
+                row(vec![
+                    ("year", int(2019)),
+                    ("q1", int(10)),
+                    ("q2", int(25)),
+                    ("q3", nothing.clone()),
+                ]),
+                row(vec![
+                    ("year", int(2020)),
+                    ("q1", int(30)),
+                    ("q2", nothing),
+                    ("q3", int(40)),
+                ]),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn explodes_a_comma_separated_column() -> Result<(), ShellError> {
+        let table = vec![
+            row(vec![("name", string("nu")), ("tags", string("shell,rust"))]),
+            row(vec![("name", string("bash")), ("tags", string("shell"))]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::explode(&table, "tags", ",")?,
+            UntaggedValue::Table(vec![
+                row(vec![("name", string("nu")), ("tags", string("shell"))]),
+                row(vec![("name", string("nu")), ("tags", string("rust"))]),
+                row(vec![("name", string("bash")), ("tags", string("shell"))]),
+            ])
+        );
+        assert!(UntaggedValue::explode(&table, "name", ",").is_ok());
+        assert!(UntaggedValue::explode(&[row(vec![("tags", int(1))])], "tags", ",").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn coalesces_first_value_that_is_not_nothing() {
+        let columns = vec!["nickname".to_string(), "name".to_string()];
+        let dict = |value: Value| match value.value {
+            UntaggedValue::Row(dict) => dict,
+            _ => unreachable!("rows are dictionaries"),
+        };
+
+        let person = dict(row(vec![
+            ("nickname", UntaggedValue::nothing().into_untagged_value()),
+            ("name", string("andres")),
+        ]));
+
+        assert_eq!(
+            UntaggedValue::coalesce_columns(&person, &columns, "display"),
+            dict(row(vec![
+                ("nickname", UntaggedValue::nothing().into_untagged_value()),
+                ("name", string("andres")),
+                ("display", string("andres")),
+            ]))
+        );
+    }
+
+    #[test]
+    fn scan_computes_running_max() -> Result<(), ShellError> {
+        let table = ints(&[3, 1, 4, 1, 5, 9, 2]);
+
+        let running_max = UntaggedValue::scan(&table, int(0), |acc, row| {
+            Ok(if row > acc { row.clone() } else { acc.clone() })
+        })?;
+
+        assert_eq!(
+            running_max,
+            UntaggedValue::Table(ints(&[3, 3, 4, 4, 5, 9, 9]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn scan_stops_at_the_first_error() {
+        let table = ints(&[1, 2, 3]);
+
+        let result = UntaggedValue::scan(&table, int(0), |_, row| {
+            if *row == int(2) {
+                Err(ShellError::untagged_runtime_error("two"))
+            } else {
+                Ok(row.clone())
+            }
+        });
+
+        assert!(result.is_err());
+    }
+
+    fn add(acc: Value, value: &Value) -> Result<Value, ShellError> {
+        match (&acc.value, &value.value) {
+            (
+                UntaggedValue::Primitive(Primitive::Int(left)),
+                UntaggedValue::Primitive(Primitive::Int(right)),
+            ) => Ok(UntaggedValue::int(left + right).into_untagged_value()),
+            _ => Err(ShellError::untagged_runtime_error("expected integers")),
+        }
+    }
+
+    #[test]
+    fn reduce_folds_addition_over_a_table() -> Result<(), ShellError> {
+        let total = UntaggedValue::reduce(&ints(&[1, 2, 3, 4]), int(0), add)?;
+
+        assert_eq!(total, int(10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reduce_propagates_errors() {
+        let values = vec![int(1), string("two"), int(3)];
+
+        assert!(UntaggedValue::reduce(&values, int(0), add).is_err());
+    }
+
+    #[test]
+    fn fill_down_fills_interior_gaps() -> Result<(), ShellError> {
+        let table: Vec<Value> = vec![nothing(), int(1), nothing(), nothing(), int(2), nothing()]
+            .into_iter()
+            .map(|value| row(vec![("reading", value)]))
+            .collect();
+
+        let filled = UntaggedValue::fill_down(&table, "reading")?;
+
+        let expected: Vec<Value> = vec![nothing(), int(1), int(1), int(1), int(2), int(2)]
+            .into_iter()
+            .map(|value| row(vec![("reading", value)]))
+            .collect();
+
+        assert_eq!(filled, UntaggedValue::Table(expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fill_down_errors_on_a_missing_column() {
+        let table = vec![row(vec![("reading", int(1))])];
+
+        assert!(UntaggedValue::fill_down(&table, "missing").is_err());
+    }
+
+    fn scores() -> Vec<Value> {
+        vec![
+            row(vec![("name", string("a")), ("score", int(20))]),
+            row(vec![("name", string("b")), ("score", int(10))]),
+            row(vec![("name", string("c")), ("score", int(20))]),
+            row(vec![("name", string("d")), ("score", int(30))]),
+        ]
+    }
+
+    fn ranks(ranked: UntaggedValue) -> Vec<Value> {
+        match ranked {
+            UntaggedValue::Table(rows) => rows
+                .iter()
+                .map(|row| match &row.value {
+                    UntaggedValue::Row(dict) => dict.get_data("rank").borrow().clone(),
+                    _ => panic!("expected a row"),
+                })
+                .collect(),
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn rank_ascending_with_a_tie() -> Result<(), ShellError> {
+        let ranked = UntaggedValue::rank(&scores(), "score", false)?;
+
+        assert_eq!(ranks(ranked), ints(&[2, 1, 2, 4]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rank_descending_with_a_tie() -> Result<(), ShellError> {
+        let ranked = UntaggedValue::rank(&scores(), "score", true)?;
+
+        assert_eq!(ranks(ranked), ints(&[2, 4, 2, 1]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sample_is_deterministic_with_a_seed() {
+        let table = ints(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+        let first = UntaggedValue::sample(&table, 4, Some(42));
+        let second = UntaggedValue::sample(&table, 4, Some(42));
+
+        assert_eq!(first, second);
+        assert_eq!(first, UntaggedValue::Table(ints(&[9, 6, 2, 10])));
+    }
+
+    #[test]
+    fn sample_more_rows_than_the_table_has() {
+        let table = ints(&[1, 2, 3]);
+
+        match UntaggedValue::sample(&table, 10, Some(7)) {
+            UntaggedValue::Table(mut rows) => {
+                rows.sort();
+                assert_eq!(rows, table);
+            }
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn shuffle_is_deterministic_with_a_seed() {
+        let table = ints(&[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let first = UntaggedValue::shuffle(&table, Some(3));
+
+        assert_eq!(first, UntaggedValue::shuffle(&table, Some(3)));
+
+        match first {
+            UntaggedValue::Table(mut rows) => {
+                rows.sort();
+                assert_eq!(rows, table);
+            }
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn roll_rows_down_and_up() {
+        let table = ints(&[1, 2, 3]);
+
+        assert_eq!(
+            UntaggedValue::roll_rows(&table, 1),
+            UntaggedValue::Table(ints(&[3, 1, 2]))
+        );
+        assert_eq!(
+            UntaggedValue::roll_rows(&table, -1),
+            UntaggedValue::Table(ints(&[2, 3, 1]))
+        );
+        assert_eq!(
+            UntaggedValue::roll_rows(&table, 4),
+            UntaggedValue::Table(ints(&[3, 1, 2]))
+        );
+    }
+
+    #[test]
+    fn roll_columns_right_and_left() -> Result<(), ShellError> {
+        let value = row(vec![("a", int(1)), ("b", int(2)), ("c", int(3))]);
+
+        let right = UntaggedValue::roll_columns(&value, 1)?;
+        let left = UntaggedValue::roll_columns(&value, -1)?;
+
+        assert_eq!(right.data_descriptors(), vec!["c", "a", "b"]);
+        assert_eq!(left.data_descriptors(), vec!["b", "c", "a"]);
+        assert_eq!(right, value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn roll_columns_errors_on_primitives() {
+        assert!(UntaggedValue::roll_columns(&int(1), 1).is_err());
+    }
+
+    #[test]
+    fn column_type_summary_of_a_mixed_column() {
+        let rows = vec![
+            row(vec![("name", string("a")), ("size", int(1))]),
+            row(vec![("name", string("b")), ("size", string("big"))]),
+            row(vec![("name", string("c")), ("size", int(3))]),
+        ];
+
+        let expected = vec![
+            row(vec![
+                ("column", string("name")),
+                ("types", table(&[string("string")])),
+            ]),
+            row(vec![
+                ("column", string("size")),
+                ("types", table(&[string("integer"), string("string")])),
+            ]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::column_type_summary(&rows),
+            UntaggedValue::Table(expected)
+        );
+    }
+
+    #[test]
+    fn coerce_column_of_strings_to_ints() -> Result<(), ShellError> {
+        let rows = vec![
+            row(vec![("count", string("1"))]),
+            row(vec![("count", string("0x10"))]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::coerce_column(&rows, "count", "integer")?,
+            UntaggedValue::Table(vec![
+                row(vec![("count", int(1))]),
+                row(vec![("count", int(16))]),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn coerce_column_reports_the_bad_value() {
+        let rows = vec![
+            row(vec![("count", string("1"))]),
+            row(vec![("count", string("two"))]),
+            row(vec![("count", string("3"))]),
+        ];
+
+        assert!(UntaggedValue::coerce_column(&rows, "count", "integer").is_err());
+        assert!(UntaggedValue::coerce_column(&rows, "count", "color").is_err());
+    }
+
+    #[test]
+    fn null_counts_of_a_table_with_gaps() {
+        let rows = vec![
+            row(vec![
+                ("name", string("a")),
+                ("size", int(1)),
+                ("owner", nothing()),
+            ]),
+            row(vec![("name", string("b")), ("size", nothing())]),
+            row(vec![
+                ("name", string("c")),
+                ("size", int(3)),
+                ("owner", string("nu")),
+            ]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::null_counts(&rows),
+            row(vec![("name", int(0)), ("size", int(1)), ("owner", int(2))]).value
+        );
+    }
+
+    fn as_int(value: &Value) -> Result<BigInt, ShellError> {
+        match &value.value {
+            UntaggedValue::Primitive(Primitive::Int(int)) => Ok(int.clone()),
+            _ => Err(ShellError::untagged_runtime_error("expected an integer")),
+        }
+    }
+
+    fn total(row: &Value) -> Result<Value, ShellError> {
+        let price = as_int(super::column_of(row, "price")?)?;
+        let tax = as_int(super::column_of(row, "tax")?)?;
+
+        Ok(UntaggedValue::int(price + tax).into_untagged_value())
+    }
+
+    #[test]
+    fn insert_column_computed_from_two_others() -> Result<(), ShellError> {
+        let rows = vec![
+            row(vec![("price", int(10)), ("tax", int(2))]),
+            row(vec![("price", int(20)), ("tax", int(4))]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::insert_column(&rows, "total", total)?,
+            UntaggedValue::Table(vec![
+                row(vec![
+                    ("price", int(10)),
+                    ("tax", int(2)),
+                    ("total", int(12))
+                ]),
+                row(vec![
+                    ("price", int(20)),
+                    ("tax", int(4)),
+                    ("total", int(24))
+                ]),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_column_propagates_errors() {
+        let rows = vec![row(vec![("price", int(10))])];
+
+        assert!(UntaggedValue::insert_column(&rows, "total", total).is_err());
+    }
+
+    #[test]
+    fn drop_nth_removes_rows_at_indices() {
+        let table = ints(&[0, 1, 2, 3, 4]);
+
+        assert_eq!(
+            UntaggedValue::drop_nth(&table, &[1, 3]),
+            UntaggedValue::Table(ints(&[0, 2, 4]))
+        );
+        assert_eq!(
+            UntaggedValue::drop_nth(&table, &[3, 10]),
+            UntaggedValue::Table(ints(&[0, 1, 2, 4]))
+        );
+    }
+
+    #[test]
+    fn every_nth_keeps_rows() -> Result<(), ShellError> {
+        let table = ints(&[0, 1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(
+            UntaggedValue::every_nth(&table, 3, false)?,
+            UntaggedValue::Table(ints(&[0, 3, 6]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn every_nth_skips_rows() -> Result<(), ShellError> {
+        let table = ints(&[0, 1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(
+            UntaggedValue::every_nth(&table, 3, true)?,
+            UntaggedValue::Table(ints(&[1, 2, 4, 5]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn every_nth_errors_on_zero_stride() {
+        assert!(UntaggedValue::every_nth(&ints(&[1]), 0, false).is_err());
+    }
+
+    fn uppercase(value: &Value) -> Result<Value, ShellError> {
+        Ok(UntaggedValue::string(value.as_string()?.to_uppercase()).into_value(&value.tag))
+    }
+
+    #[test]
+    fn update_column_uppercases_strings() -> Result<(), ShellError> {
+        let rows = vec![
+            row(vec![("name", string("andres")), ("size", int(1))]),
+            row(vec![("name", string("jonathan")), ("size", int(2))]),
+        ];
+
+        let updated = UntaggedValue::update_column(&rows, "name", uppercase)?;
+
+        assert_eq!(
+            updated,
+            UntaggedValue::Table(vec![
+                row(vec![("name", string("ANDRES")), ("size", int(1))]),
+                row(vec![("name", string("JONATHAN")), ("size", int(2))]),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_column_errors_on_missing_column() {
+        let rows = vec![
+            row(vec![("name", string("andres"))]),
+            row(vec![("size", int(2))]),
+        ];
+
+        assert!(UntaggedValue::update_column(&rows, "name", uppercase).is_err());
+    }
+
+    #[test]
+    fn default_column_fills_missing_and_empty_cells() {
+        let rows = vec![
+            row(vec![("name", string("andres")), ("lang", string("rust"))]),
+            row(vec![("name", string("jonathan"))]),
+            row(vec![("name", string("yehuda")), ("lang", nothing())]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::default_column(&rows, "lang", string("nu")),
+            UntaggedValue::Table(vec![
+                row(vec![("name", string("andres")), ("lang", string("rust"))]),
+                row(vec![("name", string("jonathan")), ("lang", string("nu"))]),
+                row(vec![("name", string("yehuda")), ("lang", string("nu"))]),
+            ])
+        );
+    }
+
+    #[test]
+    fn compact_rows_drops_rows_missing_required_columns() {
+        let rows = vec![
+            row(vec![("name", string("andres")), ("lang", string("rust"))]),
+            row(vec![("name", string("jonathan"))]),
+            row(vec![("name", nothing()), ("lang", string("nu"))]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::compact_rows(&rows, &["name".to_string(), "lang".to_string()]),
+            UntaggedValue::Table(vec![rows[0].clone()])
+        );
+        assert_eq!(
+            UntaggedValue::compact_rows(&rows, &["name".to_string()]),
+            UntaggedValue::Table(vec![rows[0].clone(), rows[1].clone()])
+        );
+    }
+
+    #[test]
+    fn compact_rows_drops_empty_rows() {
+        let rows = vec![
+            row(vec![("name", string("andres")), ("lang", nothing())]),
+            row(vec![("name", nothing()), ("lang", nothing())]),
+            row(vec![]),
+            nothing(),
+            int(1),
+        ];
+
+        assert_eq!(
+            UntaggedValue::compact_rows(&rows, &[]),
+            UntaggedValue::Table(vec![rows[0].clone(), int(1)])
+        );
+    }
+
+    fn below_three(value: &Value) -> bool {
+        as_int(value)
+            .map(|int| int < BigInt::from(3))
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn take_while_stops_at_first_failing_row() {
+        let table = ints(&[1, 2, 3, 1, 2]);
+
+        assert_eq!(
+            UntaggedValue::take_while(&table, below_three),
+            UntaggedValue::Table(ints(&[1, 2]))
+        );
+    }
+
+    #[test]
+    fn take_until_stops_at_first_matching_row() {
+        let table = ints(&[5, 4, 2, 6, 1]);
+
+        assert_eq!(
+            UntaggedValue::take_until(&table, below_three),
+            UntaggedValue::Table(ints(&[5, 4]))
+        );
+    }
+
+    fn crew() -> Vec<Value> {
+        vec![
+            row(vec![("name", string("Andres")), ("lang", string("rust"))]),
+            row(vec![
+                ("name", string("Jonathan")),
+                ("lang", string("typescript")),
+            ]),
+            row(vec![
+                ("name", string("Yehuda")),
+                ("lang", string("javascript")),
+            ]),
+        ]
+    }
+
+    #[test]
+    fn find_matches_a_substring_in_any_column() {
+        let rows = crew();
+
+        assert_eq!(
+            UntaggedValue::find(&rows, "script", false),
+            UntaggedValue::Table(vec![rows[1].clone(), rows[2].clone()])
+        );
+        assert_eq!(
+            UntaggedValue::find(&rows, "andres", false),
+            UntaggedValue::Table(vec![])
+        );
+    }
+
+    #[test]
+    fn find_matches_case_insensitively() {
+        let rows = crew();
+
+        assert_eq!(
+            UntaggedValue::find(&rows, "andres", true),
+            UntaggedValue::Table(vec![rows[0].clone()])
+        );
+    }
+
+    #[test]
+    fn filter_eq_keeps_matching_rows() -> Result<(), ShellError> {
+        let rows = vec![
+            row(vec![("name", string("andres")), ("size", int(1))]),
+            row(vec![("name", string("jonathan")), ("size", int(2))]),
+            row(vec![("name", string("yehuda")), ("size", int(1))]),
+        ];
+
+        assert_eq!(
+            UntaggedValue::filter_eq(&rows, "size", &int(1))?,
+            UntaggedValue::Table(vec![rows[0].clone(), rows[2].clone()])
+        );
+        assert_eq!(
+            UntaggedValue::filter_eq(&rows, "name", &string("sophia"))?,
+            UntaggedValue::Table(vec![])
+        );
+        assert_eq!(
+            UntaggedValue::filter_eq(&rows, "size", &string("1"))?,
+            UntaggedValue::Table(vec![])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_eq_errors_on_missing_column() {
+        let rows = vec![row(vec![("name", string("andres"))])];
+
+        assert!(UntaggedValue::filter_eq(&rows, "size", &int(1)).is_err());
+    }
+
+    fn range(from: i64, to: i64, inclusion: RangeInclusion) -> Range {
+        Range::new(
+            (
+                Primitive::Int(from.into()).spanned_unknown(),
+                RangeInclusion::Inclusive,
+            ),
+            (Primitive::Int(to.into()).spanned_unknown(), inclusion),
+        )
+    }
+
+    fn sizes() -> Vec<Value> {
+        vec![
+            row(vec![("size", int(1))]),
+            row(vec![("size", int(3))]),
+            row(vec![("size", string("4"))]),
+            row(vec![("size", int(5))]),
+            row(vec![("size", int(7))]),
+        ]
+    }
+
+    #[test]
+    fn filter_range_with_inclusive_end() -> Result<(), ShellError> {
+        let rows = sizes();
+
+        assert_eq!(
+            UntaggedValue::filter_range(&rows, "size", &range(3, 5, RangeInclusion::Inclusive))?,
+            UntaggedValue::Table(vec![rows[1].clone(), rows[3].clone()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_range_with_exclusive_end() -> Result<(), ShellError> {
+        let rows = sizes();
+
+        assert_eq!(
+            UntaggedValue::filter_range(&rows, "size", &range(1, 5, RangeInclusion::Exclusive))?,
+            UntaggedValue::Table(vec![rows[0].clone(), rows[1].clone()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn par_map_preserves_row_order() -> Result<(), ShellError> {
+        let table: Vec<Value> = (0..1000).map(int).collect();
+        let doubled: Vec<Value> = (0..1000).map(|i| int(i * 2)).collect();
+
+        assert_eq!(
+            UntaggedValue::par_map(&table, |value| add(value.clone(), value))?,
+            UntaggedValue::Table(doubled)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn par_map_returns_the_earliest_error() {
+        let table: Vec<Value> = (0..1000).map(int).collect();
+
+        let result = UntaggedValue::par_map(&table, |value| {
+            let n = as_int(value)?;
+
+            if n >= BigInt::from(500) && &n % BigInt::from(100) == BigInt::from(0) {
+                Err(ShellError::untagged_runtime_error(format!(
+                    "failed on {}",
+                    n
+                )))
+            } else {
+                Ok(value.clone())
+            }
+        });
+
+        let error = result.expect_err("rows from 500 on should fail");
+        assert_eq!(error.into_diagnostic().message, "Error: failed on 500");
+    }
+
+    #[test]
+    fn prepend_and_append_to_a_primitive() {
+        let input = int(2);
+
+        assert_eq!(
+            UntaggedValue::prepend(&input, &ints(&[0, 1])),
+            UntaggedValue::Table(ints(&[0, 1, 2]))
+        );
+        assert_eq!(
+            UntaggedValue::append(&input, &ints(&[3, 4])),
+            UntaggedValue::Table(ints(&[2, 3, 4]))
+        );
+    }
+
+    #[test]
+    fn prepend_and_append_to_a_table() {
+        let input = table(&ints(&[1, 2]));
+
+        assert_eq!(
+            UntaggedValue::prepend(&input, &ints(&[0])),
+            UntaggedValue::Table(ints(&[0, 1, 2]))
+        );
+        assert_eq!(
+            UntaggedValue::append(&input, &ints(&[3])),
+            UntaggedValue::Table(ints(&[1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn finds_index_of_first_match() {
+        let table = ints(&[1, 3, 4, 6]);
+
+        assert_eq!(UntaggedValue::find_index(&table, is_even), Some(2));
+    }
+
+    #[test]
+    fn find_index_without_a_match() {
+        let table = ints(&[1, 3, 5]);
+
+        assert_eq!(UntaggedValue::find_index(&table, is_even), None);
+    }
+
+    #[test]
+    fn partitions_even_and_odd_integers() {
+        let table = ints(&[1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(
+            UntaggedValue::partition(&table, is_even),
+            (
+                UntaggedValue::Table(ints(&[2, 4, 6])),
+                UntaggedValue::Table(ints(&[1, 3, 5]))
+            )
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::type_name::SpannedTypeName;
+use crate::value::dict::TaggedDictBuilder;
+use crate::value::{UntaggedValue, Value};
+use indexmap::IndexMap;
+use nu_errors::ShellError;
+use nu_source::Tag;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// Characters that are escaped in a URL component. Everything but the unreserved characters of RFC 3986.
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+impl Value {
+    /// Percent-encode a string value so that it can be used as part of a URL
+    pub fn url_encode(&self) -> Result<Value, ShellError> {
+        let string = self.as_forgiving_string()?;
+
+        Ok(UntaggedValue::string(encode_component(string)).into_value(&self.tag))
+    }
+
+    /// Decode the percent-escapes in a string value, erroring on malformed escapes or if the decoded bytes
+    /// aren't valid UTF-8
+    pub fn url_decode(&self) -> Result<Value, ShellError> {
+        let string = self.as_forgiving_string()?;
+
+        Ok(UntaggedValue::string(decode_component(string, &self.tag)?).into_value(&self.tag))
+    }
+
+    /// Build a URL query string like `k1=v1&k2=v2` from the columns of a row. Values are converted with
+    /// `as_lossy_string`, keys and values are percent-encoded, and Nothing values are left out.
+    pub fn to_query_string(&self) -> Result<String, ShellError> {
+        let row = match &self.value {
+            UntaggedValue::Row(row) => row,
+            _ => return Err(ShellError::type_error("row", self.spanned_type_name())),
+        };
+
+        let pairs: Vec<String> = row
+            .entries
+            .iter()
+            .filter(|(_, value)| value.is_some())
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    encode_component(key),
+                    encode_component(&value.as_lossy_string())
+                )
+            })
+            .collect();
+
+        Ok(pairs.join("&"))
+    }
+
+    /// Parse a URL query string like `k1=v1&k2=v2` into a row of strings. Keys and values are decoded, with
+    /// `+` read as a space. A key that appears more than once gets a table of all of its values.
+    pub fn from_query_string(input: &str, tag: impl Into<Tag>) -> Result<Value, ShellError> {
+        let tag = tag.into();
+        let mut entries: IndexMap<String, Vec<Value>> = IndexMap::new();
+
+        for pair in input.trim_start_matches('?').split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or_default();
+            let value = parts.next().unwrap_or_default();
+
+            let key = decode_component(&key.replace('+', " "), &tag)?;
+            let value = decode_component(&value.replace('+', " "), &tag)?;
+
+            entries
+                .entry(key)
+                .or_insert_with(Vec::new)
+                .push(UntaggedValue::string(value).into_value(&tag));
+        }
+
+        let mut row = TaggedDictBuilder::new(&tag);
+
+        for (key, mut values) in entries {
+            if values.len() == 1 {
+                row.insert_value(key, values.remove(0));
+            } else {
+                row.insert_value(key, UntaggedValue::Table(values).into_value(&tag));
+            }
+        }
+
+        Ok(row.into_value())
+    }
+}
+
+pub(crate) fn encode_component(input: &str) -> String {
+    utf8_percent_encode(input, COMPONENT).to_string()
+}
+
+pub(crate) fn decode_component(input: &str, tag: &Tag) -> Result<String, ShellError> {
+    let bytes = input.as_bytes();
+
+    for (index, byte) in bytes.iter().enumerate() {
+        if *byte == b'%' {
+            let escape = bytes.get(index + 1..index + 3);
+
+            match escape {
+                Some(digits) if digits.iter().all(u8::is_ascii_hexdigit) => {}
+                _ => {
+                    return Err(ShellError::labeled_error(
+                        "Invalid percent escape",
+                        format!("'%' at byte {} is not followed by two hex digits", index),
+                        tag,
+                    ))
+                }
+            }
+        }
+    }
+
+    percent_decode_str(input)
+        .decode_utf8()
+        .map(|decoded| decoded.into_owned())
+        .map_err(|_| {
+            ShellError::labeled_error(
+                "Invalid percent escape",
+                "escapes do not decode to valid UTF-8",
+                tag,
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{row, string};
+    use crate::{UntaggedValue, Value};
+    use nu_errors::ShellError;
+    use nu_source::Tag;
+
+    #[test]
+    fn url_encode_escapes_spaces_and_reserved_characters() -> Result<(), ShellError> {
+        assert_eq!(
+            string("a b&c=d/e?f").url_encode()?,
+            string("a%20b%26c%3Dd%2Fe%3Ff")
+        );
+        assert_eq!(string("safe-_.~").url_encode()?, string("safe-_.~"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn url_encode_and_decode_round_trip() -> Result<(), ShellError> {
+        for input in &["hello world", "a+b=c&d", "100% nu", "snowman ☃", "#/?:@"] {
+            let value = string(*input);
+
+            assert_eq!(value.url_encode()?.url_decode()?, value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_query_string_escapes_keys_and_values() -> Result<(), ShellError> {
+        let value = row(vec![
+            ("q", string("nu shell & more")),
+            ("page", UntaggedValue::int(2).into_untagged_value()),
+            ("skip", UntaggedValue::nothing().into_untagged_value()),
+        ]);
+
+        assert_eq!(value.to_query_string()?, "q=nu%20shell%20%26%20more&page=2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_query_string_errors_on_non_rows() {
+        assert!(string("q=1").to_query_string().is_err());
+    }
+
+    #[test]
+    fn from_query_string_with_simple_pairs() -> Result<(), ShellError> {
+        let value = Value::from_query_string("q=nu%20shell&lang=rust+lang", Tag::unknown())?;
+
+        assert!(value.equals_ignoring_tags(&row(vec![
+            ("q", string("nu shell")),
+            ("lang", string("rust lang"))
+        ])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_query_string_with_a_repeated_key() -> Result<(), ShellError> {
+        let value = Value::from_query_string("tag=a&page=1&tag=b", Tag::unknown())?;
+
+        assert!(value.equals_ignoring_tags(&row(vec![
+            (
+                "tag",
+                UntaggedValue::table(&[string("a"), string("b")]).into_untagged_value()
+            ),
+            ("page", string("1"))
+        ])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn url_decode_errors_on_invalid_escapes() {
+        assert!(string("100%").url_decode().is_err());
+        assert!(string("%zz").url_decode().is_err());
+        assert!(string("%ff").url_decode().is_err());
+    }
+}
@@ -20,7 +20,7 @@ mod tests {
         }
 
         pub fn expect_field(&self, field: Value) {
-            let field = match field.as_column_path() {
+            let field = match ValueExt::as_column_path(&field) {
                 Ok(column_path) => column_path,
                 Err(reason) => panic!(format!(
                     "\nExpected {:#?} to be a ColumnPath, \n\ngot {:#?}",
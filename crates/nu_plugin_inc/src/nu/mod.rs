@@ -55,7 +55,7 @@ impl Plugin for Inc {
                         value: UntaggedValue::Primitive(Primitive::ColumnPath(_)),
                         ..
                     } => {
-                        self.field = Some(table.as_column_path()?);
+                        self.field = Some(ValueExt::as_column_path(&table)?);
                     }
                     value => {
                         return Err(ShellError::type_error(
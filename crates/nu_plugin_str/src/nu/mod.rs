@@ -119,7 +119,7 @@ impl Plugin for Str {
         }
 
         if let Some(possible_field) = args.nth(0) {
-            let possible_field = possible_field.as_column_path()?;
+            let possible_field = ValueExt::as_column_path(possible_field)?;
             self.for_field(possible_field);
         }
 